@@ -0,0 +1,272 @@
+use crate::{
+    bloom::BloomFilter,
+    error::{Error, Result},
+    iter::SortedIter,
+    PrioContainer, PrioContainerMax, StablePrioContainer, StablePrioContainerMax, TopKContainer,
+};
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hash},
+};
+
+/// Container rejecting items which were pushed before. Instead of remembering every item it
+/// uses a Bloom filter, which needs a fixed amount of memory but wrongly rejects a new item
+/// as duplicate with a small probability. Since duplicates aren't looked up in the container
+/// the first pushed copy of an item is kept, even if a later copy ranks better
+pub struct ApproxUnique<C, S = RandomState> {
+    container: C,
+    filter: BloomFilter<S>,
+    rate: f64,
+}
+
+/// Keeps the `capacity` smallest approximately unique items
+pub type ApproxUniquePrioContainer<T, S = RandomState> = ApproxUnique<PrioContainer<T>, S>;
+
+/// Keeps the `capacity` biggest approximately unique items
+pub type ApproxUniquePrioContainerMax<T, S = RandomState> = ApproxUnique<PrioContainerMax<T>, S>;
+
+impl<C, S> ApproxUnique<C, S> {
+    /// Returns the wrapped prio container
+    #[inline]
+    pub fn inner(&self) -> &C {
+        &self.container
+    }
+
+    /// Returns the false positive rate the filter was sized for
+    #[inline]
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+}
+
+impl<C, S: BuildHasher> ApproxUnique<C, S> {
+    /// Returns the estimated probability of a new item currently being rejected as duplicate.
+    /// It grows with every distinct item pushed and passes the configured rate once more than
+    /// the expected amount of items got pushed
+    #[inline]
+    pub fn estimated_error(&self) -> f64 {
+        self.filter.false_positive_rate()
+    }
+}
+
+macro_rules! impl_approx {
+    ($container:ident) => {
+        impl<T: Ord + Hash> ApproxUnique<$container<T>> {
+            /// Creates a new container keeping `capacity` items. The filter gets sized to
+            /// reject at most `rate` of the new items after `expected_items` distinct items
+            /// got pushed
+            ///
+            /// # Panics
+            /// Panics if `capacity` is zero or `rate` isn't within `(0, 1)`
+            #[inline]
+            pub fn new(capacity: usize, expected_items: usize, rate: f64) -> Self {
+                Self::with_hasher(capacity, expected_items, rate, RandomState::new())
+            }
+
+            /// Like `new` but returns an error instead of panicking
+            #[inline]
+            pub fn try_new(capacity: usize, expected_items: usize, rate: f64) -> Result<Self> {
+                Self::try_with_hasher(capacity, expected_items, rate, RandomState::new())
+            }
+        }
+
+        impl<T: Ord + Hash, S: BuildHasher> ApproxUnique<$container<T>, S> {
+            /// Like `new` but the filter hashes items using `hash_builder`
+            ///
+            /// # Panics
+            /// Panics if `capacity` is zero or `rate` isn't within `(0, 1)`
+            #[inline]
+            pub fn with_hasher(
+                capacity: usize,
+                expected_items: usize,
+                rate: f64,
+                hash_builder: S,
+            ) -> Self {
+                match Self::try_with_hasher(capacity, expected_items, rate, hash_builder) {
+                    Ok(container) => container,
+                    Err(err) => panic!("{err}"),
+                }
+            }
+
+            /// Like `with_hasher` but returns an error instead of panicking
+            pub fn try_with_hasher(
+                capacity: usize,
+                expected_items: usize,
+                rate: f64,
+                hash_builder: S,
+            ) -> Result<Self> {
+                if !(rate > 0.0 && rate < 1.0) {
+                    return Err(Error::InvalidRate);
+                }
+
+                let container = $container::try_new(capacity)?;
+                let filter = BloomFilter::new(expected_items, rate, hash_builder);
+                Ok(Self {
+                    container,
+                    filter,
+                    rate,
+                })
+            }
+
+            /// Inserts `item` if it wasn't pushed before. Returns `true` if it got stored
+            #[inline]
+            pub fn insert(&mut self, item: T) -> bool {
+                let hash = self.filter.hash(&item);
+                if !self.filter.insert(hash) {
+                    self.container.inc_push(1);
+                    return false;
+                }
+                self.container.insert(item)
+            }
+
+            /// Like `insert` but returns an error if allocating memory for the item fails
+            #[inline]
+            pub fn try_insert(&mut self, item: T) -> Result<bool> {
+                let hash = self.filter.hash(&item);
+                if self.filter.contains(hash) {
+                    self.container.inc_push(1);
+                    return Ok(false);
+                }
+
+                let inserted = self.container.try_insert(item)?;
+                self.filter.insert(hash);
+                Ok(inserted)
+            }
+
+            /// Returns `true` if `item` was probably pushed before
+            #[inline]
+            pub fn seen(&self, item: &T) -> bool {
+                self.filter.contains(self.filter.hash(item))
+            }
+
+            /// Returns the worst item currently stored once the container is full
+            #[inline]
+            pub fn threshold(&self) -> Option<&T> {
+                self.container.threshold()
+            }
+
+            /// Returns `true` if `item` ranks good enough to currently be accepted by `insert`.
+            /// This doesn't check whether `item` is a duplicate
+            #[inline]
+            pub fn would_accept(&self, item: &T) -> bool {
+                self.container.would_accept(item)
+            }
+
+            /// Returns an iterator over the stored items sorted from best to worst without
+            /// consuming the container
+            #[inline]
+            pub fn iter_sorted(&self) -> SortedIter<'_, T> {
+                self.container.iter_sorted()
+            }
+
+            /// Returns the stored items sorted from best to worst without consuming the container
+            #[inline]
+            pub fn to_sorted_vec(&self) -> Vec<T>
+            where
+                T: Clone,
+            {
+                self.container.to_sorted_vec()
+            }
+
+            #[inline]
+            pub fn len(&self) -> usize {
+                self.container.len()
+            }
+
+            #[inline]
+            pub fn is_empty(&self) -> bool {
+                self.container.is_empty()
+            }
+
+            #[inline]
+            pub fn capacity(&self) -> usize {
+                self.container.capacity()
+            }
+
+            /// Returns the amount of pushed items, including the ones rejected as duplicate
+            #[inline]
+            pub fn total_pushed(&self) -> usize {
+                self.container.total_pushed()
+            }
+
+            /// Returns the stored items in no particular order without sorting them
+            #[inline]
+            pub fn into_vec(self) -> Vec<T> {
+                self.container.into_vec()
+            }
+
+            /// Returns an iterator over the stored items in no particular order
+            #[inline]
+            pub fn into_unsorted_iter(self) -> std::vec::IntoIter<T> {
+                self.into_vec().into_iter()
+            }
+        }
+
+        impl<T: Ord + Hash, S: BuildHasher> Extend<T> for ApproxUnique<$container<T>, S> {
+            #[inline]
+            fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+                for i in iter {
+                    self.insert(i);
+                }
+            }
+        }
+
+        impl<T: Ord, S> IntoIterator for ApproxUnique<$container<T>, S> {
+            type Item = <$container<T> as IntoIterator>::Item;
+
+            type IntoIter = <$container<T> as IntoIterator>::IntoIter;
+
+            #[inline]
+            fn into_iter(self) -> Self::IntoIter {
+                self.container.into_iter()
+            }
+        }
+
+        impl<T: Ord + Hash, S: BuildHasher> TopKContainer<T> for ApproxUnique<$container<T>, S> {
+            #[inline]
+            fn insert(&mut self, item: T) -> bool {
+                self.insert(item)
+            }
+
+            #[inline]
+            fn threshold(&self) -> Option<&T> {
+                self.threshold()
+            }
+
+            #[inline]
+            fn would_accept(&self, item: &T) -> bool {
+                self.would_accept(item)
+            }
+
+            #[inline]
+            fn len(&self) -> usize {
+                self.len()
+            }
+
+            #[inline]
+            fn capacity(&self) -> usize {
+                self.capacity()
+            }
+
+            #[inline]
+            fn total_pushed(&self) -> usize {
+                self.total_pushed()
+            }
+
+            #[inline]
+            fn iter_sorted(&self) -> SortedIter<'_, T> {
+                self.iter_sorted()
+            }
+
+            #[inline]
+            fn drain_sorted(&mut self) -> Vec<T> {
+                TopKContainer::drain_sorted(&mut self.container)
+            }
+        }
+    };
+}
+
+impl_approx! { PrioContainer }
+impl_approx! { PrioContainerMax }
+impl_approx! { StablePrioContainer }
+impl_approx! { StablePrioContainerMax }
@@ -0,0 +1,81 @@
+use std::{
+    f64::consts::LN_2,
+    hash::{BuildHasher, Hash},
+};
+
+/// Bloom filter remembering hashed items. Checking an item which was never inserted
+/// wrongly reports it as contained with a probability close to the configured rate once
+/// the expected amount of items got inserted
+pub(crate) struct BloomFilter<S> {
+    bits: Vec<u64>,
+    /// Amount of usable bits
+    len: u64,
+    /// Amount of bits which are set
+    ones: u64,
+    hashes: u32,
+    hash_builder: S,
+}
+
+/// The two base hashes of an item, which get combined into all bit positions
+#[derive(Clone, Copy)]
+pub(crate) struct ItemHash(u64, u64);
+
+impl<S: BuildHasher> BloomFilter<S> {
+    /// Creates a filter sized for `expected_items` with a false positive rate of `rate`.
+    /// `rate` has to be within `0..1`
+    pub(crate) fn new(expected_items: usize, rate: f64, hash_builder: S) -> Self {
+        let n = expected_items.max(1) as f64;
+        let len = (-n * rate.ln() / (LN_2 * LN_2)).ceil().max(64.0) as u64;
+        let hashes = (len as f64 / n * LN_2).round().clamp(1.0, 32.0) as u32;
+
+        Self {
+            bits: vec![0; len.div_ceil(64) as usize],
+            len,
+            ones: 0,
+            hashes,
+            hash_builder,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn hash<T: Hash + ?Sized>(&self, item: &T) -> ItemHash {
+        let h1 = self.hash_builder.hash_one(item);
+        let h2 = self.hash_builder.hash_one((u8::MAX, item));
+        // A step of zero would map all positions onto the same bit
+        ItemHash(h1, h2 | 1)
+    }
+
+    #[inline]
+    pub(crate) fn contains(&self, hash: ItemHash) -> bool {
+        positions(self.len, self.hashes, hash)
+            .all(|pos| self.bits[(pos / 64) as usize] & (1 << (pos % 64)) != 0)
+    }
+
+    /// Sets all bits of `hash`. Returns `false` if all of them were set already
+    pub(crate) fn insert(&mut self, hash: ItemHash) -> bool {
+        let mut new = false;
+        for pos in positions(self.len, self.hashes, hash) {
+            let word = &mut self.bits[(pos / 64) as usize];
+            let bit = 1 << (pos % 64);
+            if *word & bit == 0 {
+                *word |= bit;
+                self.ones += 1;
+                new = true;
+            }
+        }
+        new
+    }
+
+    /// Returns the probability of an item which was never inserted being reported as
+    /// contained, estimated from the amount of set bits
+    #[inline]
+    pub(crate) fn false_positive_rate(&self) -> f64 {
+        (self.ones as f64 / self.len as f64).powi(self.hashes as i32)
+    }
+}
+
+/// Returns the positions of the bits belonging to `hash`
+#[inline]
+fn positions(len: u64, hashes: u32, ItemHash(h1, h2): ItemHash) -> impl Iterator<Item = u64> {
+    (0..u64::from(hashes)).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % len)
+}
@@ -0,0 +1,261 @@
+pub use crate::policy::{Duplicates, Max, Min, Stable, Unique, Unstable};
+
+use crate::{
+    by::{Identified, UniqueBy},
+    error::{Error, Result},
+    policy::{Order, Ties},
+    DedupPolicy, TieBreak, TopK,
+};
+use std::{collections::hash_map::RandomState, hash::Hash, marker::PhantomData};
+
+/// Dedupe policy keeping only one item per ID returned by `F`
+#[derive(Debug, Clone, Copy)]
+pub struct UniqueId<F> {
+    id_fn: F,
+}
+
+/// Container implementing the order policy `O`, tie policy `B` and dedupe policy `D`. This
+/// resolves to a `TopK` with these policies, eg. `Container<T, Max, Stable<T>>` is a
+/// `StablePrioContainerMax<T>`
+pub type Container<T, O = Min, B = Unstable, D = Duplicates> =
+    <Builder<T, O, B, D> as Build>::Container;
+
+/// Picks the policies of a `TopK` and creates it, eg.
+/// `TopK::builder().capacity(k).max().stable().unique_by(|i| i.id).build()`
+pub struct Builder<T, O = Min, B = Unstable, D = Duplicates> {
+    capacity: usize,
+    policy: DedupPolicy,
+    order: O,
+    ties: B,
+    dedupe: D,
+    item: PhantomData<fn() -> T>,
+}
+
+/// Returns a builder for a container keeping the smallest items with duplicates in no
+/// particular order
+#[inline]
+pub fn builder<T>() -> Builder<T> {
+    Builder::new()
+}
+
+impl<T> Builder<T> {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            capacity: 0,
+            policy: DedupPolicy::Seen,
+            order: Min,
+            ties: Unstable,
+            dedupe: Duplicates,
+            item: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for Builder<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, O, B, D> Builder<T, O, B, D> {
+    /// Sets the max amount of items the container keeps. Has to be bigger than zero
+    #[inline]
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Keeps the smallest items
+    #[inline]
+    pub fn min(self) -> Builder<T, Min, B, D> {
+        self.with_order(Min)
+    }
+
+    /// Keeps the biggest items
+    #[inline]
+    pub fn max(self) -> Builder<T, Max, B, D> {
+        self.with_order(Max)
+    }
+
+    /// Ranks equal items in the order they got pushed, the last pushed item ranks best
+    #[inline]
+    pub fn stable(self) -> Builder<T, O, Stable<T>, D> {
+        self.tie_break(TieBreak::Lifo)
+    }
+
+    /// Ranks equal items according to `tie_break`
+    #[inline]
+    pub fn tie_break(self, tie_break: TieBreak<T>) -> Builder<T, O, Stable<T>, D> {
+        self.with_ties(Stable::new(tie_break))
+    }
+
+    /// Ranks equal items in no particular order
+    #[inline]
+    pub fn unstable(self) -> Builder<T, O, Unstable, D> {
+        self.with_ties(Unstable)
+    }
+
+    /// Keeps only one of equal items. Items are remembered according to the policy set by
+    /// `dedup_policy`, which defaults to `DedupPolicy::Seen`
+    #[inline]
+    pub fn unique(self) -> Builder<T, O, B, Unique<T>> {
+        self.with_dedupe(Unique::new())
+    }
+
+    /// Keeps only one of equal items, remembering items according to `policy`
+    #[inline]
+    pub fn dedup_policy(mut self, policy: DedupPolicy) -> Builder<T, O, B, Unique<T>> {
+        self.policy = policy;
+        self.unique()
+    }
+
+    /// Keeps only one item per ID returned by `id_fn` instead of comparing the items. IDs
+    /// are remembered according to the policy set by `dedup_policy` before
+    #[inline]
+    pub fn unique_by<I, F: Fn(&T) -> I>(self, id_fn: F) -> Builder<T, O, B, UniqueId<F>> {
+        self.with_dedupe(UniqueId { id_fn })
+    }
+
+    /// Keeps equal items
+    #[inline]
+    pub fn duplicates(self) -> Builder<T, O, B, Duplicates> {
+        self.with_dedupe(Duplicates)
+    }
+
+    /// Creates the container
+    ///
+    /// # Panics
+    /// Panics if the capacity is zero
+    #[inline]
+    pub fn build(self) -> Container<T, O, B, D>
+    where
+        Self: Build,
+    {
+        match self.try_build() {
+            Ok(container) => container,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    #[inline]
+    fn with_order<O2>(self, order: O2) -> Builder<T, O2, B, D> {
+        Builder {
+            capacity: self.capacity,
+            policy: self.policy,
+            order,
+            ties: self.ties,
+            dedupe: self.dedupe,
+            item: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn with_ties<B2>(self, ties: B2) -> Builder<T, O, B2, D> {
+        Builder {
+            capacity: self.capacity,
+            policy: self.policy,
+            order: self.order,
+            ties,
+            dedupe: self.dedupe,
+            item: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn with_dedupe<D2>(self, dedupe: D2) -> Builder<T, O, B, D2> {
+        Builder {
+            capacity: self.capacity,
+            policy: self.policy,
+            order: self.order,
+            ties: self.ties,
+            dedupe,
+            item: PhantomData,
+        }
+    }
+}
+
+/// Creates the container implementing a combination of policies
+pub trait Build {
+    type Container;
+
+    /// Creates the container. Returns an error if the capacity is zero
+    fn try_build(self) -> Result<Self::Container>;
+}
+
+impl<T, O, B> Build for Builder<T, O, B, Duplicates>
+where
+    T: Ord,
+    O: Order,
+    B: Ties<T>,
+{
+    type Container = TopK<T, O, B, Duplicates>;
+
+    #[inline]
+    fn try_build(self) -> Result<Self::Container> {
+        if self.capacity == 0 {
+            return Err(Error::ZeroCapacity);
+        }
+        Ok(TopK::with_parts(self.capacity, 0, self.ties, Duplicates))
+    }
+}
+
+impl<T, O, B> Build for Builder<T, O, B, Unique<T>>
+where
+    T: Ord + Clone + Hash,
+    O: Order,
+    B: Ties<T>,
+{
+    type Container = TopK<T, O, B, Unique<T>>;
+
+    #[inline]
+    fn try_build(self) -> Result<Self::Container> {
+        if self.capacity == 0 {
+            return Err(Error::ZeroCapacity);
+        }
+        let mut dedupe = self.dedupe;
+        dedupe.policy = self.policy;
+        Ok(TopK::with_parts(self.capacity, 0, self.ties, dedupe))
+    }
+}
+
+impl<T, O, I, F> Build for Builder<T, O, Unstable, UniqueId<F>>
+where
+    T: Ord,
+    O: Order,
+    I: Hash + Eq + Clone,
+    F: Fn(&T) -> I,
+{
+    type Container = UniqueBy<TopK<Identified<I, T>, O, Unstable, Unique<I>>, F>;
+
+    #[inline]
+    fn try_build(self) -> Result<Self::Container> {
+        if self.capacity == 0 {
+            return Err(Error::ZeroCapacity);
+        }
+        let container = TopK::keyed(self.capacity, self.policy, RandomState::new());
+        Ok(UniqueBy::new(container, self.dedupe.id_fn))
+    }
+}
+
+impl<T, O, I, F> Build for Builder<T, O, Stable<T>, UniqueId<F>>
+where
+    T: Ord + 'static,
+    O: Order,
+    I: Hash + Eq + Clone + 'static,
+    F: Fn(&T) -> I,
+{
+    type Container = UniqueBy<TopK<Identified<I, T>, O, Stable<Identified<I, T>>, Unique<I>>, F>;
+
+    #[inline]
+    fn try_build(self) -> Result<Self::Container> {
+        if self.capacity == 0 {
+            return Err(Error::ZeroCapacity);
+        }
+        let tie_break = self.ties.tie_break.map(|i: &Identified<I, T>| &**i);
+        let dedupe = Unique::with_hasher(self.policy, RandomState::new());
+        let container = TopK::with_parts(self.capacity, 0, Stable::new(tie_break), dedupe);
+        Ok(UniqueBy::new(container, self.dedupe.id_fn))
+    }
+}
@@ -0,0 +1,1100 @@
+use crate::{
+    error::Result,
+    iter::{ItemAt, SortedIter},
+    DedupPolicy, PrioContainer, PrioContainerMax, StablePrioContainer, StablePrioContainerMax,
+    StableUniquePrioContainer, StableUniquePrioContainerMax, TopKContainer, UniquePrioContainer,
+    UniquePrioContainerMax,
+};
+use std::{
+    cmp::Ordering,
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hash, Hasher},
+    ops::Deref,
+    sync::Arc,
+};
+
+/// Item ranked by a key which gets computed once when the item is inserted.
+/// Equality and ordering only look at the key
+pub struct Keyed<K, T> {
+    key: K,
+    item: T,
+}
+
+impl<K, T> Keyed<K, T> {
+    #[inline]
+    pub(crate) fn new(key: K, item: T) -> Self {
+        Self { key, item }
+    }
+
+    /// Returns the cached key of the item
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.item
+    }
+
+    /// Returns the key and the item
+    #[inline]
+    pub fn into_parts(self) -> (K, T) {
+        (self.key, self.item)
+    }
+}
+
+impl<K, T> Deref for Keyed<K, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.item
+    }
+}
+
+impl<K: Ord, T> PartialEq for Keyed<K, T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<K: Ord, T> Eq for Keyed<K, T> {}
+
+impl<K: Ord, T> PartialOrd for Keyed<K, T> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord, T> Ord for Keyed<K, T> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+/// Item ranked by a comparator. The comparator is stored once by the container and
+/// shared with all of its items
+pub struct Compared<T, F> {
+    item: T,
+    cmp: Arc<F>,
+}
+
+impl<T, F> Compared<T, F> {
+    #[inline]
+    pub(crate) fn new(item: T, cmp: Arc<F>) -> Self {
+        Self { item, cmp }
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.item
+    }
+}
+
+impl<T, F> Deref for Compared<T, F> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.item
+    }
+}
+
+impl<T, F: Fn(&T, &T) -> Ordering> PartialEq for Compared<T, F> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T, F: Fn(&T, &T) -> Ordering> Eq for Compared<T, F> {}
+
+impl<T, F: Fn(&T, &T) -> Ordering> PartialOrd for Compared<T, F> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T, F: Fn(&T, &T) -> Ordering> Ord for Compared<T, F> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.cmp)(&self.item, &other.item)
+    }
+}
+
+/// Like [`Keyed`] but identified by the item itself, so unique containers dedupe
+/// on the item's `Hash` and `Eq` while ranking by the key
+#[derive(Clone)]
+pub struct UniqueKeyed<K, T> {
+    key: K,
+    item: T,
+}
+
+impl<K, T> UniqueKeyed<K, T> {
+    #[inline]
+    pub(crate) fn new(key: K, item: T) -> Self {
+        Self { key, item }
+    }
+
+    /// Returns the cached key of the item
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.item
+    }
+
+    /// Returns the key and the item
+    #[inline]
+    pub fn into_parts(self) -> (K, T) {
+        (self.key, self.item)
+    }
+}
+
+impl<K, T> Deref for UniqueKeyed<K, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.item
+    }
+}
+
+impl<K, T: Hash> Hash for UniqueKeyed<K, T> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.item.hash(state);
+    }
+}
+
+impl<K, T: Eq> PartialEq for UniqueKeyed<K, T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.item == other.item
+    }
+}
+
+impl<K, T: Eq> Eq for UniqueKeyed<K, T> {}
+
+impl<K: Ord, T: Eq> PartialOrd for UniqueKeyed<K, T> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord, T: Eq> Ord for UniqueKeyed<K, T> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+/// Like [`Compared`] but identified by the item itself, so unique containers dedupe
+/// on the item's `Hash` and `Eq` while ranking by the comparator
+pub struct UniqueCompared<T, F> {
+    item: T,
+    cmp: Arc<F>,
+}
+
+impl<T, F> UniqueCompared<T, F> {
+    #[inline]
+    pub(crate) fn new(item: T, cmp: Arc<F>) -> Self {
+        Self { item, cmp }
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.item
+    }
+}
+
+impl<T: Clone, F> Clone for UniqueCompared<T, F> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(self.item.clone(), Arc::clone(&self.cmp))
+    }
+}
+
+impl<T, F> Deref for UniqueCompared<T, F> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.item
+    }
+}
+
+impl<T: Hash, F> Hash for UniqueCompared<T, F> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.item.hash(state);
+    }
+}
+
+impl<T: Eq, F> PartialEq for UniqueCompared<T, F> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.item == other.item
+    }
+}
+
+impl<T: Eq, F> Eq for UniqueCompared<T, F> {}
+
+impl<T: Eq, F: Fn(&T, &T) -> Ordering> PartialOrd for UniqueCompared<T, F> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Eq, F: Fn(&T, &T) -> Ordering> Ord for UniqueCompared<T, F> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.cmp)(&self.item, &other.item)
+    }
+}
+
+/// Item identified by an ID which gets computed once when the item is inserted. Unique
+/// containers dedupe on the ID while ranking by the item's `Ord`
+#[derive(Clone)]
+pub struct Identified<I, T> {
+    id: I,
+    item: T,
+}
+
+impl<I, T> Identified<I, T> {
+    #[inline]
+    pub(crate) fn new(id: I, item: T) -> Self {
+        Self { id, item }
+    }
+
+    /// Returns the cached ID of the item
+    #[inline]
+    pub fn id(&self) -> &I {
+        &self.id
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.item
+    }
+}
+
+impl<I, T> Deref for Identified<I, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.item
+    }
+}
+
+impl<I: Hash, T> Hash for Identified<I, T> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl<I: Eq, T> PartialEq for Identified<I, T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<I: Eq, T> Eq for Identified<I, T> {}
+
+impl<I: Eq, T: Ord> PartialOrd for Identified<I, T> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<I: Eq, T: Ord> Ord for Identified<I, T> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.item.cmp(&other.item)
+    }
+}
+
+/// Container ranking its items with a comparator instead of `Ord`. Created with
+/// the `by` constructor of any prio container
+pub struct By<C, F> {
+    container: C,
+    cmp: Arc<F>,
+}
+
+/// Container ranking its items by a key function instead of `Ord`. The key gets
+/// computed once per inserted item and is stored next to it. Created with the
+/// `by_key` constructor of any prio container
+pub struct ByKey<C, F> {
+    container: C,
+    key_fn: F,
+}
+
+/// Unique container identifying its items by an ID function instead of their `Hash` and
+/// `Eq`, while ranking stays on the items' `Ord`. The ID gets computed once per inserted item
+/// and is stored next to it. Created with the `unique_by` constructor of any unique
+/// prio container
+pub struct UniqueBy<C, F> {
+    container: C,
+    id_fn: F,
+}
+
+impl<C, F> By<C, F> {
+    /// Returns the wrapped prio container
+    #[inline]
+    pub fn inner(&self) -> &C {
+        &self.container
+    }
+}
+
+impl<C, F> ByKey<C, F> {
+    /// Returns the wrapped prio container
+    #[inline]
+    pub fn inner(&self) -> &C {
+        &self.container
+    }
+}
+
+impl<C, F> UniqueBy<C, F> {
+    #[inline]
+    pub(crate) fn new(container: C, id_fn: F) -> Self {
+        Self { container, id_fn }
+    }
+
+    /// Returns the wrapped prio container
+    #[inline]
+    pub fn inner(&self) -> &C {
+        &self.container
+    }
+}
+
+macro_rules! impl_by {
+    (
+        $container:ident,
+        $keyed:ident,
+        $compared:ident,
+        [$($t_bound:tt)*],
+        [$($k_bound:tt)*],
+        |$out:ident| $unwrap:expr
+    ) => {
+        impl<T $($t_bound)*, F: Fn(&T, &T) -> Ordering> $container<$compared<T, F>> {
+            /// Creates a new container with `capacity` which ranks its items using `cmp`
+            /// instead of `Ord`
+            #[inline]
+            pub fn by(capacity: usize, cmp: F) -> By<Self, F> {
+                let container = Self::new(capacity);
+                let cmp = Arc::new(cmp);
+                By { container, cmp }
+            }
+        }
+
+        impl<T $($t_bound)*, K: Ord $($k_bound)*> $container<$keyed<K, T>> {
+            /// Creates a new container with `capacity` which ranks its items by the key
+            /// returned from `key_fn` instead of `Ord`
+            #[inline]
+            pub fn by_key<F: Fn(&T) -> K>(capacity: usize, key_fn: F) -> ByKey<Self, F> {
+                let container = Self::new(capacity);
+                ByKey { container, key_fn }
+            }
+        }
+
+        impl<T $($t_bound)*, F> By<$container<$compared<T, F>>, F>
+        where
+            F: Fn(&T, &T) -> Ordering,
+        {
+            /// Inserts a new item into the container
+            #[inline]
+            pub fn insert(&mut self, item: T) -> bool {
+                self.container.insert($compared::new(item, Arc::clone(&self.cmp)))
+            }
+
+            /// Like `insert` but returns an error if allocating memory for the item fails
+            #[inline]
+            pub fn try_insert(&mut self, item: T) -> Result<bool> {
+                self.container.try_insert($compared::new(item, Arc::clone(&self.cmp)))
+            }
+
+            /// Merges `other` into the container, keeping the best items of both
+            #[inline]
+            pub fn merge(&mut self, other: Self) {
+                self.container.merge(other.container)
+            }
+
+            /// Removes all items for which `f` returns `false`
+            #[inline]
+            pub fn retain<G: FnMut(&T) -> bool>(&mut self, mut f: G) {
+                self.container.retain(|i| f(i))
+            }
+
+            /// Removes a stored item equal to `item`. Returns `true` if it was stored
+            #[inline]
+            pub fn remove(&mut self, item: &T) -> bool
+            where
+                T: PartialEq,
+            {
+                self.container.remove_by(|i| **i == *item)
+            }
+
+            /// Changes the capacity of the container. Returns `true` if the container grew after
+            /// it already dropped items
+            #[inline]
+            pub fn set_capacity(&mut self, capacity: usize) -> Result<bool> {
+                self.container.set_capacity(capacity)
+            }
+
+            /// Returns the worst item currently stored once the container is full
+            #[inline]
+            pub fn threshold(&self) -> Option<&T> {
+                self.container.threshold().map(|i| &**i)
+            }
+
+            /// Returns `true` if `item` would currently be accepted by `insert`
+            #[inline]
+            pub fn would_accept(&self, item: &T) -> bool {
+                self.container.would_accept_by(|worst| (self.cmp)(item, worst))
+            }
+
+            /// Returns an iterator over the stored items sorted from best to worst without
+            /// consuming the container
+            #[inline]
+            pub fn iter_sorted(&self) -> SortedIter<'_, T> {
+                SortedIter::new(self.container.sorted_positions(), self)
+            }
+
+            /// Returns the stored items sorted from best to worst without consuming the container
+            #[inline]
+            pub fn to_sorted_vec(&self) -> Vec<T>
+            where
+                T: Clone,
+            {
+                self.container.sorted_refs().map(|i| (**i).clone()).collect()
+            }
+
+            /// Inserts the item built by `f` if `bound` would currently be accepted. `bound`
+            /// has to rank at least as good as the built item
+            #[inline]
+            pub fn insert_with<G: FnOnce() -> T>(&mut self, bound: &T, f: G) -> bool {
+                let cmp = &self.cmp;
+                self.container.insert_with_by(
+                    |worst| cmp(bound, worst),
+                    || $compared::new(f(), Arc::clone(cmp)),
+                )
+            }
+
+            #[inline]
+            pub fn len(&self) -> usize {
+                self.container.len()
+            }
+
+            #[inline]
+            pub fn is_empty(&self) -> bool {
+                self.container.is_empty()
+            }
+
+            #[inline]
+            pub fn capacity(&self) -> usize {
+                self.container.capacity()
+            }
+
+            #[inline]
+            pub fn total_pushed(&self) -> usize {
+                self.container.total_pushed()
+            }
+
+            /// Returns the stored items in no particular order without sorting them
+            #[inline]
+            pub fn into_vec(self) -> Vec<T> {
+                self.container.into_vec().into_iter().map(|$out| $unwrap).collect()
+            }
+
+            /// Returns an iterator over the stored items in no particular order
+            #[inline]
+            pub fn into_unsorted_iter(self) -> std::vec::IntoIter<T> {
+                self.into_vec().into_iter()
+            }
+        }
+
+        impl<T $($t_bound)*, F> ItemAt<T> for By<$container<$compared<T, F>>, F> {
+            #[inline]
+            fn item_at(&self, pos: usize) -> &T {
+                self.container.item_at(pos)
+            }
+        }
+
+        impl<T $($t_bound)*, K, F> ItemAt<T> for ByKey<$container<$keyed<K, T>>, F> {
+            #[inline]
+            fn item_at(&self, pos: usize) -> &T {
+                self.container.item_at(pos)
+            }
+        }
+
+        impl<T $($t_bound)*, K, F> ByKey<$container<$keyed<K, T>>, F>
+        where
+            K: Ord $($k_bound)*,
+            F: Fn(&T) -> K,
+        {
+            /// Inserts a new item into the container
+            #[inline]
+            pub fn insert(&mut self, item: T) -> bool {
+                let key = (self.key_fn)(&item);
+                self.container.insert($keyed::new(key, item))
+            }
+
+            /// Like `insert` but returns an error if allocating memory for the item fails
+            #[inline]
+            pub fn try_insert(&mut self, item: T) -> Result<bool> {
+                let key = (self.key_fn)(&item);
+                self.container.try_insert($keyed::new(key, item))
+            }
+
+            /// Merges `other` into the container, keeping the best items of both
+            #[inline]
+            pub fn merge(&mut self, other: Self) {
+                self.container.merge(other.container)
+            }
+
+            /// Removes all items for which `f` returns `false`
+            #[inline]
+            pub fn retain<G: FnMut(&T) -> bool>(&mut self, mut f: G) {
+                self.container.retain(|i| f(i))
+            }
+
+            /// Removes a stored item equal to `item`. Returns `true` if it was stored
+            #[inline]
+            pub fn remove(&mut self, item: &T) -> bool
+            where
+                T: PartialEq,
+            {
+                self.container.remove_by(|i| **i == *item)
+            }
+
+            /// Changes the capacity of the container. Returns `true` if the container grew after
+            /// it already dropped items
+            #[inline]
+            pub fn set_capacity(&mut self, capacity: usize) -> Result<bool> {
+                self.container.set_capacity(capacity)
+            }
+
+            /// Returns the worst item currently stored once the container is full
+            #[inline]
+            pub fn threshold(&self) -> Option<&T> {
+                self.container.threshold().map(|i| &**i)
+            }
+
+            /// Returns the key of the worst item currently stored once the container is full
+            #[inline]
+            pub fn threshold_key(&self) -> Option<&K> {
+                self.container.threshold().map(|i| i.key())
+            }
+
+            /// Returns `true` if `item` would currently be accepted by `insert`
+            #[inline]
+            pub fn would_accept(&self, item: &T) -> bool {
+                self.would_accept_key(&(self.key_fn)(item))
+            }
+
+            /// Returns an iterator over the stored items sorted from best to worst without
+            /// consuming the container
+            #[inline]
+            pub fn iter_sorted(&self) -> SortedIter<'_, T> {
+                SortedIter::new(self.container.sorted_positions(), self)
+            }
+
+            /// Returns the stored items sorted from best to worst without consuming the container
+            #[inline]
+            pub fn to_sorted_vec(&self) -> Vec<T>
+            where
+                T: Clone,
+            {
+                self.container.sorted_refs().map(|i| (**i).clone()).collect()
+            }
+
+            /// Returns `true` if an item with `key` would currently be accepted by `insert`
+            #[inline]
+            pub fn would_accept_key(&self, key: &K) -> bool {
+                self.container.would_accept_by(|worst| key.cmp(worst.key()))
+            }
+
+            /// Inserts the item built by `f` if an item with key `bound` would currently be
+            /// accepted. `bound` has to rank at least as good as the built item's key. This
+            /// allows skipping the construction of items which can't make it into the
+            /// container anyway
+            #[inline]
+            pub fn insert_with<G: FnOnce() -> T>(&mut self, bound: &K, f: G) -> bool {
+                let key_fn = &self.key_fn;
+                self.container.insert_with_by(
+                    |worst| bound.cmp(worst.key()),
+                    || {
+                        let item = f();
+                        $keyed::new(key_fn(&item), item)
+                    },
+                )
+            }
+
+            #[inline]
+            pub fn len(&self) -> usize {
+                self.container.len()
+            }
+
+            #[inline]
+            pub fn is_empty(&self) -> bool {
+                self.container.is_empty()
+            }
+
+            #[inline]
+            pub fn capacity(&self) -> usize {
+                self.container.capacity()
+            }
+
+            #[inline]
+            pub fn total_pushed(&self) -> usize {
+                self.container.total_pushed()
+            }
+
+            /// Returns the stored items in no particular order without sorting them
+            #[inline]
+            pub fn into_vec(self) -> Vec<T> {
+                self.container.into_vec().into_iter().map(|$out| $unwrap).collect()
+            }
+
+            /// Returns an iterator over the stored items in no particular order
+            #[inline]
+            pub fn into_unsorted_iter(self) -> std::vec::IntoIter<T> {
+                self.into_vec().into_iter()
+            }
+        }
+
+        impl<T $($t_bound)*, F> TopKContainer<T> for By<$container<$compared<T, F>>, F>
+        where
+            F: Fn(&T, &T) -> Ordering,
+        {
+            #[inline]
+            fn insert(&mut self, item: T) -> bool {
+                self.insert(item)
+            }
+
+            #[inline]
+            fn threshold(&self) -> Option<&T> {
+                self.threshold()
+            }
+
+            #[inline]
+            fn would_accept(&self, item: &T) -> bool {
+                self.would_accept(item)
+            }
+
+            #[inline]
+            fn len(&self) -> usize {
+                self.len()
+            }
+
+            #[inline]
+            fn capacity(&self) -> usize {
+                self.capacity()
+            }
+
+            #[inline]
+            fn total_pushed(&self) -> usize {
+                self.total_pushed()
+            }
+
+            #[inline]
+            fn iter_sorted(&self) -> SortedIter<'_, T> {
+                self.iter_sorted()
+            }
+
+            #[inline]
+            fn drain_sorted(&mut self) -> Vec<T> {
+                let sorted = TopKContainer::drain_sorted(&mut self.container);
+                sorted.into_iter().map(|i| i.into_inner()).collect()
+            }
+        }
+
+        impl<T $($t_bound)*, K, F> TopKContainer<T> for ByKey<$container<$keyed<K, T>>, F>
+        where
+            K: Ord $($k_bound)*,
+            F: Fn(&T) -> K,
+        {
+            #[inline]
+            fn insert(&mut self, item: T) -> bool {
+                self.insert(item)
+            }
+
+            #[inline]
+            fn threshold(&self) -> Option<&T> {
+                self.threshold()
+            }
+
+            #[inline]
+            fn would_accept(&self, item: &T) -> bool {
+                self.would_accept(item)
+            }
+
+            #[inline]
+            fn len(&self) -> usize {
+                self.len()
+            }
+
+            #[inline]
+            fn capacity(&self) -> usize {
+                self.capacity()
+            }
+
+            #[inline]
+            fn total_pushed(&self) -> usize {
+                self.total_pushed()
+            }
+
+            #[inline]
+            fn iter_sorted(&self) -> SortedIter<'_, T> {
+                self.iter_sorted()
+            }
+
+            #[inline]
+            fn drain_sorted(&mut self) -> Vec<T> {
+                let sorted = TopKContainer::drain_sorted(&mut self.container);
+                sorted.into_iter().map(|i| i.into_inner()).collect()
+            }
+        }
+
+        impl<T $($t_bound)*, F> Extend<T> for By<$container<$compared<T, F>>, F>
+        where
+            F: Fn(&T, &T) -> Ordering,
+        {
+            #[inline]
+            fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+                for i in iter {
+                    self.insert(i);
+                }
+            }
+        }
+
+        impl<T $($t_bound)*, K, F> Extend<T> for ByKey<$container<$keyed<K, T>>, F>
+        where
+            K: Ord $($k_bound)*,
+            F: Fn(&T) -> K,
+        {
+            #[inline]
+            fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+                for i in iter {
+                    self.insert(i);
+                }
+            }
+        }
+
+        impl<T $($t_bound)*, F> IntoIterator for By<$container<$compared<T, F>>, F>
+        where
+            F: Fn(&T, &T) -> Ordering,
+        {
+            type Item = T;
+
+            type IntoIter = std::iter::Map<
+                <$container<$compared<T, F>> as IntoIterator>::IntoIter,
+                fn(<$container<$compared<T, F>> as IntoIterator>::Item) -> T,
+            >;
+
+            #[inline]
+            fn into_iter(self) -> Self::IntoIter {
+                self.container.into_iter().map(|$out| $unwrap)
+            }
+        }
+
+        impl<T $($t_bound)*, K, F> IntoIterator for ByKey<$container<$keyed<K, T>>, F>
+        where
+            K: Ord $($k_bound)*,
+            F: Fn(&T) -> K,
+        {
+            type Item = T;
+
+            type IntoIter = std::iter::Map<
+                <$container<$keyed<K, T>> as IntoIterator>::IntoIter,
+                fn(<$container<$keyed<K, T>> as IntoIterator>::Item) -> T,
+            >;
+
+            #[inline]
+            fn into_iter(self) -> Self::IntoIter {
+                self.container.into_iter().map(|$out| $unwrap)
+            }
+        }
+    };
+}
+
+impl_by! { PrioContainer, Keyed, Compared, [], [], |i| i.into_inner() }
+impl_by! { PrioContainerMax, Keyed, Compared, [], [], |i| i.into_inner() }
+impl_by! { StablePrioContainer, Keyed, Compared, [], [], |i| i.into_inner() }
+impl_by! { StablePrioContainerMax, Keyed, Compared, [], [], |i| i.into_inner() }
+
+impl_by! {
+    UniquePrioContainer, UniqueKeyed, UniqueCompared,
+    [: Hash + Eq + Clone], [+ Clone], |i| i.into_inner()
+}
+impl_by! {
+    UniquePrioContainerMax, UniqueKeyed, UniqueCompared,
+    [: Hash + Eq + Clone], [+ Clone], |i| i.into_inner()
+}
+impl_by! {
+    StableUniquePrioContainer, UniqueKeyed, UniqueCompared,
+    [: Hash + Eq + Clone], [+ Clone], |i| i.into_inner()
+}
+impl_by! {
+    StableUniquePrioContainerMax, UniqueKeyed, UniqueCompared,
+    [: Hash + Eq + Clone], [+ Clone], |i| i.into_inner()
+}
+
+macro_rules! impl_unique_by {
+    ($container:ident) => {
+        impl<T: Ord, I: Hash + Eq + Clone> $container<Identified<I, T>, RandomState, I> {
+            /// Creates a new container with `capacity` which identifies its items by the ID
+            /// returned from `id_fn` instead of their `Hash` and `Eq`
+            #[inline]
+            pub fn unique_by<F: Fn(&T) -> I>(capacity: usize, id_fn: F) -> UniqueBy<Self, F> {
+                Self::unique_by_with_hasher(capacity, id_fn, RandomState::new())
+            }
+        }
+
+        impl<T, I, S> $container<Identified<I, T>, S, I>
+        where
+            T: Ord,
+            I: Hash + Eq + Clone,
+            S: BuildHasher,
+        {
+            /// Like `unique_by` but hashes the IDs using `hash_builder`
+            #[inline]
+            pub fn unique_by_with_hasher<F: Fn(&T) -> I>(
+                capacity: usize,
+                id_fn: F,
+                hash_builder: S,
+            ) -> UniqueBy<Self, F> {
+                let container = Self::keyed(capacity, DedupPolicy::Seen, hash_builder);
+                UniqueBy { container, id_fn }
+            }
+        }
+
+        impl<T, I, S, F> UniqueBy<$container<Identified<I, T>, S, I>, F>
+        where
+            T: Ord,
+            I: Hash + Eq + Clone,
+            S: BuildHasher,
+            F: Fn(&T) -> I,
+        {
+            /// Inserts a new item into the container. An item with an already seen ID only
+            /// replaces the stored one if it ranks better
+            #[inline]
+            pub fn insert(&mut self, item: T) -> bool {
+                let id = (self.id_fn)(&item);
+                self.container.insert(Identified::new(id, item))
+            }
+
+            /// Like `insert` but returns an error if allocating memory for the item fails
+            #[inline]
+            pub fn try_insert(&mut self, item: T) -> Result<bool> {
+                let id = (self.id_fn)(&item);
+                self.container.try_insert(Identified::new(id, item))
+            }
+
+            /// Merges `other` into the container, keeping the best items of both. Items with
+            /// the same ID are only kept once
+            #[inline]
+            pub fn merge(&mut self, other: Self) {
+                self.container.merge(other.container)
+            }
+
+            /// Removes all items for which `f` returns `false`. Their IDs are forgotten
+            #[inline]
+            pub fn retain<G: FnMut(&T) -> bool>(&mut self, mut f: G) {
+                self.container.retain(|i| f(i))
+            }
+
+            /// Removes the stored item with the ID of `item` and forgets the ID. Returns `true`
+            /// if an item with that ID was stored
+            #[inline]
+            pub fn remove(&mut self, item: &T) -> bool {
+                self.container.remove_key(&(self.id_fn)(item))
+            }
+
+            /// Changes the capacity of the container. Returns `true` if the container grew after
+            /// it already dropped items
+            #[inline]
+            pub fn set_capacity(&mut self, capacity: usize) -> Result<bool> {
+                self.container.set_capacity(capacity)
+            }
+
+            /// Returns the worst item currently stored once the container is full
+            #[inline]
+            pub fn threshold(&self) -> Option<&T> {
+                self.container.threshold().map(|i| &**i)
+            }
+
+            /// Returns `true` if `item` ranks good enough to currently be accepted by `insert`.
+            /// This doesn't check whether its ID is a duplicate
+            #[inline]
+            pub fn would_accept(&self, item: &T) -> bool {
+                self.container.would_accept_by(|worst| item.cmp(worst))
+            }
+
+            /// Returns an iterator over the stored items sorted from best to worst without
+            /// consuming the container
+            #[inline]
+            pub fn iter_sorted(&self) -> SortedIter<'_, T> {
+                SortedIter::new(self.container.sorted_positions(), self)
+            }
+
+            /// Returns the stored items sorted from best to worst without consuming the container
+            #[inline]
+            pub fn to_sorted_vec(&self) -> Vec<T>
+            where
+                T: Clone,
+            {
+                self.container
+                    .sorted_refs()
+                    .map(|i| (**i).clone())
+                    .collect()
+            }
+
+            #[inline]
+            pub fn len(&self) -> usize {
+                self.container.len()
+            }
+
+            #[inline]
+            pub fn is_empty(&self) -> bool {
+                self.container.is_empty()
+            }
+
+            #[inline]
+            pub fn capacity(&self) -> usize {
+                self.container.capacity()
+            }
+
+            #[inline]
+            pub fn total_pushed(&self) -> usize {
+                self.container.total_pushed()
+            }
+
+            /// Returns the stored items in no particular order without sorting them
+            #[inline]
+            pub fn into_vec(self) -> Vec<T> {
+                self.container
+                    .into_vec()
+                    .into_iter()
+                    .map(|i| i.into_inner())
+                    .collect()
+            }
+
+            /// Returns an iterator over the stored items in no particular order
+            #[inline]
+            pub fn into_unsorted_iter(self) -> std::vec::IntoIter<T> {
+                self.into_vec().into_iter()
+            }
+        }
+
+        impl<T, I, S, F> ItemAt<T> for UniqueBy<$container<Identified<I, T>, S, I>, F> {
+            #[inline]
+            fn item_at(&self, pos: usize) -> &T {
+                self.container.item_at(pos)
+            }
+        }
+
+        impl<T, I, S, F> TopKContainer<T> for UniqueBy<$container<Identified<I, T>, S, I>, F>
+        where
+            T: Ord,
+            I: Hash + Eq + Clone,
+            S: BuildHasher,
+            F: Fn(&T) -> I,
+        {
+            #[inline]
+            fn insert(&mut self, item: T) -> bool {
+                self.insert(item)
+            }
+
+            #[inline]
+            fn threshold(&self) -> Option<&T> {
+                self.threshold()
+            }
+
+            #[inline]
+            fn would_accept(&self, item: &T) -> bool {
+                self.would_accept(item)
+            }
+
+            #[inline]
+            fn len(&self) -> usize {
+                self.len()
+            }
+
+            #[inline]
+            fn capacity(&self) -> usize {
+                self.capacity()
+            }
+
+            #[inline]
+            fn total_pushed(&self) -> usize {
+                self.total_pushed()
+            }
+
+            #[inline]
+            fn iter_sorted(&self) -> SortedIter<'_, T> {
+                self.iter_sorted()
+            }
+
+            #[inline]
+            fn drain_sorted(&mut self) -> Vec<T> {
+                let sorted = TopKContainer::drain_sorted(&mut self.container);
+                sorted.into_iter().map(|i| i.into_inner()).collect()
+            }
+        }
+
+        impl<T, I, S, F> Extend<T> for UniqueBy<$container<Identified<I, T>, S, I>, F>
+        where
+            T: Ord,
+            I: Hash + Eq + Clone,
+            S: BuildHasher,
+            F: Fn(&T) -> I,
+        {
+            #[inline]
+            fn extend<J: IntoIterator<Item = T>>(&mut self, iter: J) {
+                for i in iter {
+                    self.insert(i);
+                }
+            }
+        }
+
+        impl<T, I, S, F> IntoIterator for UniqueBy<$container<Identified<I, T>, S, I>, F>
+        where
+            T: Ord,
+            I: Hash + Eq + Clone,
+            S: BuildHasher,
+            F: Fn(&T) -> I,
+        {
+            type Item = T;
+
+            type IntoIter = std::iter::Map<
+                <$container<Identified<I, T>, S, I> as IntoIterator>::IntoIter,
+                fn(Identified<I, T>) -> T,
+            >;
+
+            #[inline]
+            fn into_iter(self) -> Self::IntoIter {
+                self.container.into_iter().map(|i| i.into_inner())
+            }
+        }
+    };
+}
+
+impl_unique_by! { UniquePrioContainer }
+impl_unique_by! { UniquePrioContainerMax }
+impl_unique_by! { StableUniquePrioContainer }
+impl_unique_by! { StableUniquePrioContainerMax }
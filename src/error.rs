@@ -0,0 +1,40 @@
+use std::{collections::TryReserveError, fmt::Display};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Errors returned by the fallible methods of the prio containers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The capacity of a container can't be zero
+    ZeroCapacity,
+    /// Allocating memory for a new item failed
+    Alloc(TryReserveError),
+    /// A false positive rate has to be bigger than zero and smaller than one
+    InvalidRate,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::ZeroCapacity => write!(f, "Capacity can't be zero"),
+            Error::Alloc(err) => write!(f, "Allocation failed: {err}"),
+            Error::InvalidRate => write!(f, "False positive rate has to be within (0, 1)"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::ZeroCapacity | Error::InvalidRate => None,
+            Error::Alloc(err) => Some(err),
+        }
+    }
+}
+
+impl From<TryReserveError> for Error {
+    #[inline]
+    fn from(err: TryReserveError) -> Self {
+        Self::Alloc(err)
+    }
+}
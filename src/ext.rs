@@ -0,0 +1,245 @@
+use crate::{
+    PrioContainer, PrioContainerMax, StablePrioContainer, StablePrioContainerMax, TieBreak,
+    UniquePrioContainer, UniquePrioContainerMax,
+};
+use std::hash::Hash;
+
+/// Shortcuts for collecting the best items of an iterator into a prio container. All methods
+/// consume the whole iterator and return the kept items sorted from best to worst. `n` may be
+/// zero, in which case an empty vec gets returned without consuming any item
+pub trait TopKExt: Iterator + Sized {
+    /// Returns the `n` biggest items
+    #[inline]
+    fn top_k(self, n: usize) -> Vec<Self::Item>
+    where
+        Self::Item: Ord,
+    {
+        collect(self, n, PrioContainerMax::new)
+    }
+
+    /// Returns the `n` smallest items
+    #[inline]
+    fn bottom_k(self, n: usize) -> Vec<Self::Item>
+    where
+        Self::Item: Ord,
+    {
+        collect(self, n, PrioContainer::new)
+    }
+
+    /// Returns the `n` biggest items. Equal items keep the order in which the iterator
+    /// yielded them
+    #[inline]
+    fn top_k_stable(self, n: usize) -> Vec<Self::Item>
+    where
+        Self::Item: Ord,
+    {
+        collect(self, n, |n| {
+            StablePrioContainerMax::with_tie_break(n, TieBreak::Fifo)
+        })
+    }
+
+    /// Returns the `n` smallest items. Equal items keep the order in which the iterator
+    /// yielded them
+    #[inline]
+    fn bottom_k_stable(self, n: usize) -> Vec<Self::Item>
+    where
+        Self::Item: Ord,
+    {
+        collect(self, n, |n| {
+            StablePrioContainer::with_tie_break(n, TieBreak::Fifo)
+        })
+    }
+
+    /// Returns the `n` items with the biggest key returned by `key_fn`
+    #[inline]
+    fn top_k_by_key<K, F>(self, n: usize, key_fn: F) -> Vec<Self::Item>
+    where
+        K: Ord,
+        F: Fn(&Self::Item) -> K,
+    {
+        collect(self, n, |n| PrioContainerMax::by_key(n, key_fn))
+    }
+
+    /// Returns the `n` items with the smallest key returned by `key_fn`
+    #[inline]
+    fn bottom_k_by_key<K, F>(self, n: usize, key_fn: F) -> Vec<Self::Item>
+    where
+        K: Ord,
+        F: Fn(&Self::Item) -> K,
+    {
+        collect(self, n, |n| PrioContainer::by_key(n, key_fn))
+    }
+
+    /// Returns the `n` biggest items, skipping items equal to one yielded before
+    #[inline]
+    fn top_k_unique(self, n: usize) -> Vec<Self::Item>
+    where
+        Self::Item: Ord + Hash + Clone,
+    {
+        collect(self, n, UniquePrioContainerMax::new)
+    }
+
+    /// Returns the `n` smallest items, skipping items equal to one yielded before
+    #[inline]
+    fn bottom_k_unique(self, n: usize) -> Vec<Self::Item>
+    where
+        Self::Item: Ord + Hash + Clone,
+    {
+        collect(self, n, UniquePrioContainer::new)
+    }
+
+    /// Returns the `n` biggest items or the first error. Items after the error don't get
+    /// consumed. A zero `n` returns `Ok` right away without looking for errors
+    #[inline]
+    fn try_top_k<T, E>(self, n: usize) -> Result<Vec<T>, E>
+    where
+        Self: Iterator<Item = Result<T, E>>,
+        T: Ord,
+    {
+        try_collect(self, |items| items.top_k(n))
+    }
+
+    /// Returns the `n` smallest items or the first error. Items after the error don't get
+    /// consumed. A zero `n` returns `Ok` right away without looking for errors
+    #[inline]
+    fn try_bottom_k<T, E>(self, n: usize) -> Result<Vec<T>, E>
+    where
+        Self: Iterator<Item = Result<T, E>>,
+        T: Ord,
+    {
+        try_collect(self, |items| items.bottom_k(n))
+    }
+
+    /// Like `top_k_stable` but returns the first error instead. Items after the error don't
+    /// get consumed. A zero `n` returns `Ok` right away without looking for errors
+    #[inline]
+    fn try_top_k_stable<T, E>(self, n: usize) -> Result<Vec<T>, E>
+    where
+        Self: Iterator<Item = Result<T, E>>,
+        T: Ord,
+    {
+        try_collect(self, |items| items.top_k_stable(n))
+    }
+
+    /// Like `bottom_k_stable` but returns the first error instead. Items after the error
+    /// don't get consumed. A zero `n` returns `Ok` right away without looking for errors
+    #[inline]
+    fn try_bottom_k_stable<T, E>(self, n: usize) -> Result<Vec<T>, E>
+    where
+        Self: Iterator<Item = Result<T, E>>,
+        T: Ord,
+    {
+        try_collect(self, |items| items.bottom_k_stable(n))
+    }
+
+    /// Like `top_k_by_key` but returns the first error instead. Items after the error don't
+    /// get consumed. A zero `n` returns `Ok` right away without looking for errors
+    #[inline]
+    fn try_top_k_by_key<T, E, K, F>(self, n: usize, key_fn: F) -> Result<Vec<T>, E>
+    where
+        Self: Iterator<Item = Result<T, E>>,
+        K: Ord,
+        F: Fn(&T) -> K,
+    {
+        try_collect(self, |items| items.top_k_by_key(n, key_fn))
+    }
+
+    /// Like `bottom_k_by_key` but returns the first error instead. Items after the error
+    /// don't get consumed. A zero `n` returns `Ok` right away without looking for errors
+    #[inline]
+    fn try_bottom_k_by_key<T, E, K, F>(self, n: usize, key_fn: F) -> Result<Vec<T>, E>
+    where
+        Self: Iterator<Item = Result<T, E>>,
+        K: Ord,
+        F: Fn(&T) -> K,
+    {
+        try_collect(self, |items| items.bottom_k_by_key(n, key_fn))
+    }
+
+    /// Like `top_k_unique` but returns the first error instead. Items after the error don't
+    /// get consumed. A zero `n` returns `Ok` right away without looking for errors
+    #[inline]
+    fn try_top_k_unique<T, E>(self, n: usize) -> Result<Vec<T>, E>
+    where
+        Self: Iterator<Item = Result<T, E>>,
+        T: Ord + Hash + Clone,
+    {
+        try_collect(self, |items| items.top_k_unique(n))
+    }
+
+    /// Like `bottom_k_unique` but returns the first error instead. Items after the error
+    /// don't get consumed. A zero `n` returns `Ok` right away without looking for errors
+    #[inline]
+    fn try_bottom_k_unique<T, E>(self, n: usize) -> Result<Vec<T>, E>
+    where
+        Self: Iterator<Item = Result<T, E>>,
+        T: Ord + Hash + Clone,
+    {
+        try_collect(self, |items| items.bottom_k_unique(n))
+    }
+}
+
+impl<I: Iterator> TopKExt for I {}
+
+/// Feeds all items of `iter` into the container created by `new` and returns the kept items
+/// sorted from best to worst. Nothing gets consumed if `n` is zero
+#[inline]
+fn collect<I, C, N>(iter: I, n: usize, new: N) -> Vec<I::Item>
+where
+    I: Iterator,
+    C: Extend<I::Item> + IntoIterator<Item = I::Item>,
+    C::IntoIter: DoubleEndedIterator,
+    N: FnOnce(usize) -> C,
+{
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let mut container = new(n);
+    container.extend(iter);
+    container.into_iter().rev().collect()
+}
+
+/// Passes the `Ok` items of `iter` to `f` until the first error, which gets returned instead
+/// of `f`'s result
+#[inline]
+fn try_collect<I, T, E, F>(iter: I, f: F) -> Result<Vec<T>, E>
+where
+    I: Iterator<Item = Result<T, E>>,
+    F: FnOnce(&mut Oks<I, E>) -> Vec<T>,
+{
+    let mut items = Oks { iter, err: None };
+    let top = f(&mut items);
+    match items.err {
+        Some(err) => Err(err),
+        None => Ok(top),
+    }
+}
+
+/// Yields the `Ok` items of `iter` and stops at the first error, which gets kept in `err`
+struct Oks<I, E> {
+    iter: I,
+    err: Option<E>,
+}
+
+impl<I, T, E> Iterator for Oks<I, E>
+where
+    I: Iterator<Item = Result<T, E>>,
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.err.is_some() {
+            return None;
+        }
+
+        match self.iter.next()? {
+            Ok(item) => Some(item),
+            Err(err) => {
+                self.err = Some(err);
+                None
+            }
+        }
+    }
+}
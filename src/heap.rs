@@ -0,0 +1,351 @@
+use crate::{
+    by::Identified,
+    policy::{Dedupe, Duplicates, Positions, Unique},
+    stable::item::HeapItem,
+    DedupPolicy,
+};
+use std::{
+    cmp::Ordering,
+    collections::TryReserveError,
+    hash::{BuildHasher, Hash},
+    mem,
+};
+
+/// Stored items which wrap the key a container looks them up or breaks ties by. This is
+/// implemented for every item type the containers store internally, like `HeapItem<T>`,
+/// so generic code over a container only needs to carry the bound along
+pub trait HeapKey<K> {
+    /// Returns the key wrapped by the item
+    fn key(&self) -> &K;
+}
+
+impl<T> HeapKey<T> for T {
+    #[inline]
+    fn key(&self) -> &T {
+        self
+    }
+}
+
+impl<T> HeapKey<T> for HeapItem<T> {
+    #[inline]
+    fn key(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<I, T> HeapKey<I> for Identified<I, T> {
+    #[inline]
+    fn key(&self) -> &I {
+        self.id()
+    }
+}
+
+impl<I, T> HeapKey<I> for HeapItem<Identified<I, T>> {
+    #[inline]
+    fn key(&self) -> &I {
+        self.inner.id()
+    }
+}
+
+/// Binary heap keeping the item comparing biggest according to the comparator passed to
+/// each call on top. `X` gets told about every position change, which lets unique
+/// containers look up items by their key in O(1) and replace or remove them in O(log n)
+pub(crate) struct Heap<E, X = Duplicates> {
+    items: Vec<E>,
+    pub(crate) index: X,
+}
+
+impl<E, X> Heap<E, X> {
+    #[inline]
+    pub(crate) fn new(index: X) -> Self {
+        Self::with_capacity(0, index)
+    }
+
+    #[inline]
+    pub(crate) fn with_capacity(items: usize, index: X) -> Self {
+        Self {
+            items: Vec::with_capacity(items),
+            index,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    #[inline]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    #[inline]
+    pub(crate) fn as_slice(&self) -> &[E] {
+        &self.items
+    }
+
+    /// Returns the biggest item
+    #[inline]
+    pub(crate) fn peek(&self) -> Option<&E> {
+        self.items.first()
+    }
+
+    /// Reserves space for one more item
+    #[inline]
+    pub(crate) fn try_reserve(&mut self) -> Result<(), TryReserveError> {
+        self.items.try_reserve(1)
+    }
+
+    /// Returns the stored items in heap order and the index
+    #[inline]
+    pub(crate) fn into_parts(self) -> (Vec<E>, X) {
+        (self.items, self.index)
+    }
+}
+
+impl<E, X: Positions<E>> Heap<E, X> {
+    /// Creates a heap from `items` in any order
+    pub(crate) fn from_vec<C>(items: Vec<E>, index: X, cmp: C) -> Self
+    where
+        C: Fn(&E, &E) -> Ordering + Copy,
+    {
+        let mut heap = Self { items, index };
+        heap.heapify(cmp);
+        heap
+    }
+
+    pub(crate) fn push<C: Fn(&E, &E) -> Ordering + Copy>(&mut self, item: E, cmp: C) {
+        let pos = self.items.len();
+        self.items.push(item);
+        self.set_position(pos);
+        self.sift_up(pos, cmp);
+    }
+
+    /// Removes the biggest item, which counts as evicted
+    pub(crate) fn pop<C: Fn(&E, &E) -> Ordering + Copy>(&mut self, cmp: C) -> Option<E> {
+        if self.items.is_empty() {
+            return None;
+        }
+
+        let item = self.remove_at(0, cmp);
+        self.index.evicted(&item);
+        Some(item)
+    }
+
+    /// Replaces the biggest item with `item` and returns the old one, which counts as evicted
+    ///
+    /// # Panics
+    /// Panics if the heap is empty
+    pub(crate) fn replace_top<C>(&mut self, item: E, cmp: C) -> E
+    where
+        C: Fn(&E, &E) -> Ordering + Copy,
+    {
+        let old = mem::replace(&mut self.items[0], item);
+        self.index.evicted(&old);
+        self.set_position(0);
+        self.sift_down(0, cmp);
+        old
+    }
+
+    /// Replaces the item at `pos` with `item`, which has to have the same key
+    pub(crate) fn replace_at<C>(&mut self, pos: usize, item: E, cmp: C) -> E
+    where
+        C: Fn(&E, &E) -> Ordering + Copy,
+    {
+        let old = mem::replace(&mut self.items[pos], item);
+        self.sift(pos, cmp);
+        old
+    }
+
+    /// Replaces the item at `pos` with the one `f` builds from it, which has to have the
+    /// same key
+    pub(crate) fn update<F, C>(&mut self, pos: usize, f: F, cmp: C)
+    where
+        F: FnOnce(E) -> E,
+        C: Fn(&E, &E) -> Ordering + Copy,
+    {
+        let item = self.remove_at(pos, cmp);
+        self.push(f(item), cmp);
+    }
+
+    /// Removes the first item for which `f` returns `true`, which counts as removed
+    pub(crate) fn remove_where<F, C>(&mut self, f: F, cmp: C) -> Option<E>
+    where
+        F: FnMut(&E) -> bool,
+        C: Fn(&E, &E) -> Ordering + Copy,
+    {
+        let pos = self.items.iter().position(f)?;
+        self.index.removed(&self.items[pos]);
+        Some(self.remove_at(pos, cmp))
+    }
+
+    /// Removes all items for which `f` returns `false`, which count as removed. Returns `true`
+    /// if any item got removed
+    pub(crate) fn retain<F, C>(&mut self, mut f: F, cmp: C) -> bool
+    where
+        F: FnMut(&E) -> bool,
+        C: Fn(&E, &E) -> Ordering + Copy,
+    {
+        let len = self.items.len();
+        let index = &mut self.index;
+        self.items.retain(|i| {
+            let keep = f(i);
+            if !keep {
+                index.removed(i);
+            }
+            keep
+        });
+
+        if self.items.len() == len {
+            return false;
+        }
+
+        self.heapify(cmp);
+        true
+    }
+
+    /// Changes all items with `f` without touching their keys and restores the heap order
+    pub(crate) fn update_all<F, C>(&mut self, f: F, cmp: C)
+    where
+        F: FnMut(&mut E),
+        C: Fn(&E, &E) -> Ordering + Copy,
+    {
+        self.items.iter_mut().for_each(f);
+        self.heapify(cmp);
+    }
+
+    /// Removes all items in heap order, which count as evicted
+    pub(crate) fn take(&mut self) -> Vec<E> {
+        let items = mem::take(&mut self.items);
+        for i in items.iter() {
+            self.index.evicted(i);
+        }
+        items
+    }
+
+    /// Removes the stored item equal to `item` and lets the index forget it
+    pub(crate) fn remove_item<T, C>(&mut self, item: &T, cmp: C) -> Option<E>
+    where
+        X: Dedupe<T, E>,
+        C: Fn(&E, &E) -> Ordering + Copy,
+    {
+        let pos = self.index.forget(&self.items, item)?;
+        Some(self.remove_at(pos, cmp))
+    }
+
+    /// Removes the item at `pos` without telling the index about it
+    pub(crate) fn remove_at<C: Fn(&E, &E) -> Ordering + Copy>(&mut self, pos: usize, cmp: C) -> E {
+        let item = self.items.swap_remove(pos);
+        if pos < self.items.len() {
+            self.set_position(pos);
+            self.sift(pos, cmp);
+        }
+        item
+    }
+
+    /// Restores the heap order and the positions of all items
+    fn heapify<C: Fn(&E, &E) -> Ordering + Copy>(&mut self, cmp: C) {
+        for pos in 0..self.items.len() {
+            self.set_position(pos);
+        }
+        for pos in (0..self.items.len() / 2).rev() {
+            self.sift_down(pos, cmp);
+        }
+    }
+
+    #[inline]
+    fn sift<C: Fn(&E, &E) -> Ordering + Copy>(&mut self, pos: usize, cmp: C) {
+        if self.sift_up(pos, cmp) == pos {
+            self.sift_down(pos, cmp);
+        }
+    }
+
+    /// Moves the item at `pos` up until its parent is bigger and returns its new position
+    fn sift_up<C: Fn(&E, &E) -> Ordering + Copy>(&mut self, mut pos: usize, cmp: C) -> usize {
+        while pos > 0 {
+            let parent = (pos - 1) / 2;
+            if cmp(&self.items[pos], &self.items[parent]).is_le() {
+                break;
+            }
+            self.swap(pos, parent);
+            pos = parent;
+        }
+        pos
+    }
+
+    /// Moves the item at `pos` down until both of its children are smaller
+    fn sift_down<C: Fn(&E, &E) -> Ordering + Copy>(&mut self, mut pos: usize, cmp: C) {
+        loop {
+            let left = 2 * pos + 1;
+            if left >= self.items.len() {
+                break;
+            }
+
+            let right = left + 1;
+            let child =
+                if right < self.items.len() && cmp(&self.items[right], &self.items[left]).is_gt() {
+                    right
+                } else {
+                    left
+                };
+
+            if cmp(&self.items[child], &self.items[pos]).is_le() {
+                break;
+            }
+            self.swap(pos, child);
+            pos = child;
+        }
+    }
+
+    #[inline]
+    fn swap(&mut self, a: usize, b: usize) {
+        self.items.swap(a, b);
+        self.set_position(a);
+        self.set_position(b);
+    }
+
+    #[inline]
+    fn set_position(&mut self, pos: usize) {
+        self.index.set(&self.items[pos], pos);
+    }
+}
+
+impl<E, K, S> Heap<E, Unique<K, S>>
+where
+    E: HeapKey<K>,
+    K: Hash + Eq + Clone,
+    S: BuildHasher,
+{
+    /// Returns `true` if an item with `key` is remembered
+    #[inline]
+    pub(crate) fn seen(&self, key: &K) -> bool {
+        self.index.seen(key)
+    }
+
+    /// Returns the position of the stored item with `key`
+    #[inline]
+    pub(crate) fn position(&self, key: &K) -> Option<usize> {
+        self.index.get(key)
+    }
+
+    /// Remembers `key` as seen without storing an item for it, unless only the keys of
+    /// stored items get remembered
+    #[inline]
+    pub(crate) fn remember(&mut self, key: K) {
+        self.index.remember(key)
+    }
+
+    /// Removes the item with `key` if it's stored and forgets the key
+    pub(crate) fn forget<C>(&mut self, key: &K, cmp: C) -> Option<E>
+    where
+        C: Fn(&E, &E) -> Ordering + Copy,
+    {
+        let pos = self.index.forget(key)?;
+        Some(self.remove_at(pos, cmp))
+    }
+
+    #[inline]
+    pub(crate) fn policy(&self) -> DedupPolicy {
+        self.index.policy()
+    }
+}
@@ -1,110 +1,342 @@
-use crate::stable::item::HeapItem;
-use std::{cmp::Reverse, collections::BinaryHeap};
+use crate::{
+    heap::Heap,
+    policy::{Duplicates, Entry, Max, Min, Order},
+    stable::item::HeapItem,
+};
+use std::{
+    cmp::Ordering,
+    iter::{FusedIterator, Rev},
+    marker::PhantomData,
+    slice, vec,
+};
 
-/// Iterator over a binary heap sorted
-pub struct SortedHeapIter<T> {
-    inner: BinaryHeap<T>,
+/// Items of a consumed container. They get popped off the heap worst-first until the
+/// first item is requested from the other end, which sorts the remaining items
+enum HeapIter<E> {
+    Heap(Heap<E, Duplicates>),
+    /// Remaining items sorted from best to worst
+    Sorted(vec::IntoIter<E>),
 }
 
-impl<T: Ord> SortedHeapIter<T> {
+/// Iterator over the items of a consumed container. Yields the items from worst to best,
+/// `best_first` reverses the order
+pub struct IntoIter<T, O = Min, E = T> {
+    inner: HeapIter<E>,
+    item: PhantomData<fn() -> (T, O)>,
+}
+
+/// Iterator over a consumed container keeping the smallest items
+pub type SortedHeapIter<T> = IntoIter<T>;
+
+/// Iterator over a consumed container keeping the biggest items
+pub type SortedHeapIterMax<T> = IntoIter<T, Max>;
+
+/// Iterator over a consumed stable container keeping the smallest items
+pub type StableHeapIter<T> = IntoIter<T, Min, HeapItem<T>>;
+
+/// Iterator over a consumed stable container keeping the biggest items
+pub type StableHeapIterMax<T> = IntoIter<T, Max, HeapItem<T>>;
+
+impl<T: Ord, O: Order, E: Entry<T>> IntoIter<T, O, E> {
     #[inline]
-    pub(crate) fn new(heap: BinaryHeap<T>) -> Self {
-        Self { inner: heap }
+    pub(crate) fn new(items: Vec<E>) -> Self {
+        Self {
+            inner: HeapIter::Heap(Heap::from_vec(items, Duplicates, Self::rank)),
+            item: PhantomData,
+        }
     }
-}
 
-impl<T: Ord> Iterator for SortedHeapIter<T> {
-    type Item = T;
+    /// Yields the remaining items from worst to best, which is the default order
+    #[inline]
+    pub fn worst_first(self) -> Self {
+        self
+    }
 
-    #[inline(always)]
-    fn next(&mut self) -> Option<T> {
-        self.inner.pop()
+    /// Yields the remaining items from best to worst
+    #[inline]
+    pub fn best_first(self) -> Rev<Self> {
+        self.rev()
     }
 
+    /// Returns the remaining items in no particular order. Unlike collecting the
+    /// iterator this takes O(n) since the items don't get sorted
     #[inline]
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        let exact = self.inner.len();
-        (exact, Some(exact))
+    pub fn into_vec(self) -> Vec<T> {
+        self.into_entries().into_iter().map(E::into_item).collect()
     }
-}
 
-/// Iterator over a binary heap sorted
-pub struct SortedHeapIterMax<T> {
-    inner: BinaryHeap<Reverse<T>>,
+    #[inline]
+    fn worst(&mut self) -> Option<E> {
+        match &mut self.inner {
+            HeapIter::Heap(heap) => heap.pop(Self::rank),
+            HeapIter::Sorted(items) => items.next_back(),
+        }
+    }
+
+    #[inline]
+    fn best(&mut self) -> Option<E> {
+        if let HeapIter::Sorted(items) = &mut self.inner {
+            return items.next();
+        }
+
+        let mut items = self.take_sorted().into_iter();
+        let best = items.next();
+        self.inner = HeapIter::Sorted(items);
+        best
+    }
+
+    /// Returns the remaining entries in no particular order
+    #[inline]
+    fn into_entries(self) -> Vec<E> {
+        match self.inner {
+            HeapIter::Heap(heap) => heap.into_parts().0,
+            HeapIter::Sorted(items) => items.collect(),
+        }
+    }
+
+    /// Takes the remaining entries sorted from best to worst
+    #[inline]
+    fn take_sorted(&mut self) -> Vec<E> {
+        let inner = std::mem::replace(&mut self.inner, HeapIter::Sorted(Vec::new().into_iter()));
+        match inner {
+            HeapIter::Heap(heap) => {
+                let mut items = heap.into_parts().0;
+                items.sort_unstable_by(Self::rank);
+                items
+            }
+            HeapIter::Sorted(items) => items.collect(),
+        }
+    }
+
+    #[inline]
+    fn rank(a: &E, b: &E) -> Ordering {
+        a.rank::<O>(b)
+    }
 }
 
-impl<T: Ord> SortedHeapIterMax<T> {
+impl<T: Ord, O: Order> IntoIter<T, O, HeapItem<T>> {
+    /// Returns the remaining items along with their insertion sequence in no particular
+    /// order, so they can be sorted later
     #[inline]
-    pub(crate) fn new(heap: BinaryHeap<Reverse<T>>) -> Self {
-        Self { inner: heap }
+    pub fn into_heap_items(self) -> Vec<HeapItem<T>> {
+        self.into_entries()
+    }
+
+    /// Turns the remaining items into an iterator yielding `(rank, seq, item)` from best to
+    /// worst, where `seq` is the item's insertion sequence
+    #[inline]
+    pub fn ranked(mut self, mode: RankMode) -> Ranked<T> {
+        let mut items = self.take_sorted();
+        items.reverse();
+        Ranked::new(items, mode)
+    }
+
+    /// Turns the remaining items into an iterator yielding `(rank, seq, item)` from best to
+    /// worst, where equal items share the mean of their ordinal ranks
+    #[inline]
+    pub fn ranked_fractional(self) -> RankedFractional<T> {
+        RankedFractional {
+            inner: self.ranked(RankMode::Competition),
+        }
     }
 }
 
-impl<T: Ord> Iterator for SortedHeapIterMax<T> {
+impl<T: Ord, O: Order, E: Entry<T>> Iterator for IntoIter<T, O, E> {
     type Item = T;
 
     #[inline(always)]
     fn next(&mut self) -> Option<T> {
-        self.inner.pop().map(|i| i.0)
+        self.worst().map(E::into_item)
     }
 
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let exact = self.inner.len();
+        let exact = match &self.inner {
+            HeapIter::Heap(heap) => heap.len(),
+            HeapIter::Sorted(items) => items.len(),
+        };
         (exact, Some(exact))
     }
 }
 
-/// Iterator over a binary heap sorted
-pub struct StableHeapIter<T> {
-    inner: BinaryHeap<HeapItem<T>>,
+impl<T: Ord, O: Order, E: Entry<T>> DoubleEndedIterator for IntoIter<T, O, E> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        self.best().map(E::into_item)
+    }
+}
+
+impl<T: Ord, O: Order, E: Entry<T>> ExactSizeIterator for IntoIter<T, O, E> {}
+
+impl<T: Ord, O: Order, E: Entry<T>> FusedIterator for IntoIter<T, O, E> {}
+
+/// How `Ranked` numbers items which compare equal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RankMode {
+    /// Every item gets its own rank ("1234")
+    #[default]
+    Ordinal,
+    /// Equal items share the best rank of their group and the following ranks get skipped
+    /// ("1224")
+    Competition,
+    /// Equal items share a rank and no ranks get skipped ("1223")
+    Dense,
+}
+
+/// Iterator yielding `(rank, seq, item)` from best to worst. Ranks start at 1 and are
+/// numbered according to a `RankMode`
+pub struct Ranked<T> {
+    /// Remaining items sorted from worst to best
+    items: Vec<HeapItem<T>>,
+    mode: RankMode,
+    /// Amount of items yielded so far
+    yielded: usize,
+    /// Amount of groups of equal items started so far
+    groups: usize,
+    /// Rank of the current group of equal items
+    rank: usize,
+    /// Amount of items in the current group of equal items
+    group_len: usize,
+    /// Amount of items of the current group which weren't yielded yet
+    left: usize,
 }
 
-impl<T> StableHeapIter<T> {
+impl<T: Ord> Ranked<T> {
     #[inline]
-    pub(crate) fn new(heap: BinaryHeap<HeapItem<T>>) -> Self {
-        Self { inner: heap }
+    fn new(items: Vec<HeapItem<T>>, mode: RankMode) -> Self {
+        Self {
+            items,
+            mode,
+            yielded: 0,
+            groups: 0,
+            rank: 0,
+            group_len: 0,
+            left: 0,
+        }
     }
 }
 
-impl<T: Ord> Iterator for StableHeapIter<T> {
-    type Item = T;
+impl<T: Ord> Iterator for Ranked<T> {
+    type Item = (usize, usize, T);
 
-    #[inline(always)]
-    fn next(&mut self) -> Option<T> {
-        self.inner.pop().map(|i| i.into_inner())
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.items.pop()?;
+        self.yielded += 1;
+
+        if self.left == 0 {
+            let rest = self.items.iter().rev();
+            let len = 1 + rest
+                .take_while(|i| i.inner.cmp(&item.inner).is_eq())
+                .count();
+            self.groups += 1;
+            self.group_len = len;
+            self.left = len;
+            self.rank = match self.mode {
+                RankMode::Ordinal | RankMode::Competition => self.yielded,
+                RankMode::Dense => self.groups,
+            };
+        }
+        self.left -= 1;
+
+        let rank = match self.mode {
+            RankMode::Ordinal => self.yielded,
+            _ => self.rank,
+        };
+        Some((rank, item.counter, item.inner))
     }
 
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let exact = self.inner.len();
+        let exact = self.items.len();
         (exact, Some(exact))
     }
 }
 
-/// Iterator over a binary heap sorted
-pub struct StableHeapIterMax<T> {
-    inner: BinaryHeap<HeapItem<Reverse<T>>>,
+impl<T: Ord> ExactSizeIterator for Ranked<T> {}
+
+impl<T: Ord> FusedIterator for Ranked<T> {}
+
+/// Iterator yielding `(rank, seq, item)` from best to worst. Equal items share the mean of
+/// the ranks they'd get with `RankMode::Ordinal` ("1 2.5 2.5 4")
+pub struct RankedFractional<T> {
+    inner: Ranked<T>,
 }
 
-impl<T> StableHeapIterMax<T> {
+impl<T: Ord> Iterator for RankedFractional<T> {
+    type Item = (f64, usize, T);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (rank, seq, item) = self.inner.next()?;
+        let rank = rank as f64 + (self.inner.group_len - 1) as f64 / 2.0;
+        Some((rank, seq, item))
+    }
+
     #[inline]
-    pub(crate) fn new(heap: BinaryHeap<HeapItem<Reverse<T>>>) -> Self {
-        Self { inner: heap }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
     }
 }
 
-impl<T: Ord> Iterator for StableHeapIterMax<T> {
-    type Item = T;
+impl<T: Ord> ExactSizeIterator for RankedFractional<T> {}
+
+impl<T: Ord> FusedIterator for RankedFractional<T> {}
+
+/// Container whose stored items can be looked up by their heap position
+pub(crate) trait ItemAt<T> {
+    /// Returns the item stored at heap position `pos`
+    fn item_at(&self, pos: usize) -> &T;
+}
+
+/// Iterator over the items of a container sorted from best to worst, borrowing the
+/// container instead of consuming it
+pub struct SortedIter<'a, T> {
+    /// Heap positions of the remaining items sorted from best to worst
+    order: slice::Iter<'a, usize>,
+    items: &'a dyn ItemAt<T>,
+}
+
+impl<'a, T> SortedIter<'a, T> {
+    #[inline]
+    pub(crate) fn new(order: &'a [usize], items: &'a dyn ItemAt<T>) -> Self {
+        Self {
+            order: order.iter(),
+            items,
+        }
+    }
+}
+
+impl<'a, T> Iterator for SortedIter<'a, T> {
+    type Item = &'a T;
 
     #[inline(always)]
-    fn next(&mut self) -> Option<T> {
-        self.inner.pop().map(|i| i.into_inner().0)
+    fn next(&mut self) -> Option<&'a T> {
+        self.order.next().map(|pos| self.items.item_at(*pos))
     }
 
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let exact = self.inner.len();
-        (exact, Some(exact))
+        self.order.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for SortedIter<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a T> {
+        self.order.next_back().map(|pos| self.items.item_at(*pos))
     }
 }
+
+impl<T> ExactSizeIterator for SortedIter<'_, T> {}
+
+impl<T> FusedIterator for SortedIter<'_, T> {}
+
+/// Returns the positions of `items` sorted ascending by `cmp`
+pub(crate) fn sorted_order<E, C>(items: &[E], cmp: C) -> Vec<usize>
+where
+    C: Fn(&E, &E) -> Ordering,
+{
+    let mut order = (0..items.len()).collect::<Vec<_>>();
+    order.sort_unstable_by(|a, b| cmp(&items[*a], &items[*b]));
+    order
+}
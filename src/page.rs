@@ -0,0 +1,129 @@
+use crate::{
+    error::{Error, Result},
+    iter::SortedIter,
+    PrioContainer, PrioContainerMax, StablePrioContainer, StablePrioContainerMax,
+    StableUniquePrioContainer, StableUniquePrioContainerMax, TopKContainer, UniquePrioContainer,
+    UniquePrioContainerMax,
+};
+use std::{hash::Hash, iter::Skip, vec};
+
+/// Container keeping only the items ranked `offset..offset + limit`. Internally it stores
+/// the best `offset + limit` items but only yields the requested page
+pub struct Paginated<C> {
+    container: C,
+    offset: usize,
+    limit: usize,
+}
+
+impl<C> Paginated<C> {
+    /// Returns the rank of the first item of the page
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the max amount of items on the page
+    #[inline]
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Returns the wrapped prio container
+    #[inline]
+    pub fn inner(&self) -> &C {
+        &self.container
+    }
+}
+
+macro_rules! impl_paginated {
+    ($container:ident, [$($bound:tt)*]) => {
+        impl<T: $($bound)*> Paginated<$container<T>> {
+            /// Creates a new paginated container keeping the items ranked `offset..offset + limit`
+            ///
+            /// # Panics
+            /// Panics if `limit` is zero
+            #[inline]
+            pub fn new(offset: usize, limit: usize) -> Self {
+                if limit == 0 {
+                    panic!("Limit can't be zero");
+                }
+                let container = $container::new(offset.saturating_add(limit));
+                Self { container, offset, limit }
+            }
+
+            /// Creates a new paginated container keeping the items ranked `offset..offset + limit`.
+            /// Returns an error instead of panicking if `limit` is zero
+            #[inline]
+            pub fn try_new(offset: usize, limit: usize) -> Result<Self> {
+                if limit == 0 {
+                    return Err(Error::ZeroCapacity);
+                }
+                let container = $container::try_new(offset.saturating_add(limit))?;
+                Ok(Self { container, offset, limit })
+            }
+
+            /// Inserts a new item. Returns `true` if the item got stored, which doesn't
+            /// necessarily mean that it's ranked within the page
+            #[inline]
+            pub fn insert(&mut self, item: T) -> bool {
+                self.container.insert(item)
+            }
+
+            /// Returns the amount of items currently on the page
+            #[inline]
+            pub fn len(&self) -> usize {
+                self.container.len().saturating_sub(self.offset)
+            }
+
+            /// Returns `true` if there is no item on the page
+            #[inline]
+            pub fn is_empty(&self) -> bool {
+                self.len() == 0
+            }
+
+            /// Returns the total amount of items pushed into the container
+            #[inline]
+            pub fn total_pushed(&self) -> usize {
+                self.container.total_pushed()
+            }
+
+            /// Returns an iterator over the items of the page sorted from best to worst without
+            /// consuming the container
+            #[inline]
+            pub fn iter(&self) -> Skip<SortedIter<'_, T>> {
+                self.container.iter_sorted().skip(self.offset)
+            }
+        }
+
+        impl<T: $($bound)*> Extend<T> for Paginated<$container<T>> {
+            #[inline]
+            fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+                for i in iter {
+                    self.insert(i);
+                }
+            }
+        }
+
+        impl<T: $($bound)*> IntoIterator for Paginated<$container<T>> {
+            type Item = T;
+
+            type IntoIter = Skip<vec::IntoIter<T>>;
+
+            /// Returns the items of the page sorted from best to worst
+            #[inline]
+            fn into_iter(mut self) -> Self::IntoIter {
+                let sorted = TopKContainer::drain_sorted(&mut self.container);
+                sorted.into_iter().skip(self.offset)
+            }
+        }
+    };
+}
+
+impl_paginated! { PrioContainer, [Ord] }
+impl_paginated! { PrioContainerMax, [Ord] }
+impl_paginated! { StablePrioContainer, [Ord] }
+impl_paginated! { StablePrioContainerMax, [Ord] }
+impl_paginated! { UniquePrioContainer, [Ord + Clone + Hash] }
+impl_paginated! { UniquePrioContainerMax, [Ord + Clone + Hash] }
+impl_paginated! { StableUniquePrioContainer, [Ord + Clone + Hash] }
+impl_paginated! { StableUniquePrioContainerMax, [Ord + Clone + Hash] }
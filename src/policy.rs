@@ -0,0 +1,435 @@
+use crate::{
+    heap::HeapKey,
+    stable::{item::HeapItem, TieBreak},
+    DedupPolicy,
+};
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::RandomState, HashMap, TryReserveError},
+    hash::{BuildHasher, Hash},
+};
+
+/// Decides which items a container keeps
+pub trait Order {
+    /// Turns the natural order of two items into their rank, the better item compares smaller
+    fn rank(ord: Ordering) -> Ordering;
+
+    /// Compares the ranks of `a` and `b`, the better item compares smaller
+    #[inline]
+    fn cmp<T: Ord>(a: &T, b: &T) -> Ordering {
+        Self::rank(a.cmp(b))
+    }
+}
+
+/// Order policy keeping the smallest items
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Min;
+
+/// Order policy keeping the biggest items
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Max;
+
+impl Order for Min {
+    #[inline]
+    fn rank(ord: Ordering) -> Ordering {
+        ord
+    }
+}
+
+impl Order for Max {
+    #[inline]
+    fn rank(ord: Ordering) -> Ordering {
+        ord.reverse()
+    }
+}
+
+/// Item stored by a container for every item pushed into it
+pub trait Entry<T>: HeapKey<T> {
+    fn into_item(self) -> T;
+
+    /// Returns the push counter the entry got created with, zero if it doesn't track one
+    fn counter(&self) -> usize;
+
+    /// Compares the ranks of two entries, the better entry compares smaller
+    fn rank<O: Order>(&self, other: &Self) -> Ordering
+    where
+        T: Ord;
+}
+
+impl<T> Entry<T> for T {
+    #[inline]
+    fn into_item(self) -> T {
+        self
+    }
+
+    #[inline]
+    fn counter(&self) -> usize {
+        0
+    }
+
+    #[inline]
+    fn rank<O: Order>(&self, other: &Self) -> Ordering
+    where
+        T: Ord,
+    {
+        O::cmp(self, other)
+    }
+}
+
+impl<T> Entry<T> for HeapItem<T> {
+    #[inline]
+    fn into_item(self) -> T {
+        self.inner
+    }
+
+    #[inline]
+    fn counter(&self) -> usize {
+        self.counter
+    }
+
+    #[inline]
+    fn rank<O: Order>(&self, other: &Self) -> Ordering
+    where
+        T: Ord,
+    {
+        O::cmp(&self.inner, &other.inner).then_with(|| self.cmp_tie(other))
+    }
+}
+
+/// Decides how a container ranks items which compare equal
+pub trait Ties<T> {
+    /// What the container stores for each item
+    type Entry: Entry<T>;
+
+    /// Wraps `item`, which got pushed as `counter`-th item
+    fn entry(&self, item: T, counter: usize) -> Self::Entry;
+
+    /// Returns `true` if a new item pushed as `counter`-th item beats `worst`, which it
+    /// compares equal to. `item` is `None` if the new item isn't known yet
+    fn beats(&self, worst: &Self::Entry, counter: usize, item: Option<&T>) -> bool;
+}
+
+/// Tie policy ranking equal items in no particular order
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Unstable;
+
+impl<T> Ties<T> for Unstable {
+    type Entry = T;
+
+    #[inline]
+    fn entry(&self, item: T, _counter: usize) -> T {
+        item
+    }
+
+    #[inline]
+    fn beats(&self, _worst: &T, _counter: usize, _item: Option<&T>) -> bool {
+        false
+    }
+}
+
+/// Tie policy ranking equal items according to a `TieBreak`, which looks at the `K` each
+/// item wraps
+#[derive(Debug)]
+pub struct Stable<K> {
+    pub(crate) tie_break: TieBreak<K>,
+}
+
+impl<K> Default for Stable<K> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(TieBreak::Lifo)
+    }
+}
+
+impl<K> Stable<K> {
+    #[inline]
+    pub fn new(tie_break: TieBreak<K>) -> Self {
+        Self { tie_break }
+    }
+
+    /// Returns the tie break ranking equal items
+    #[inline]
+    pub fn tie_break(&self) -> &TieBreak<K> {
+        &self.tie_break
+    }
+}
+
+impl<T: HeapKey<K>, K> Ties<T> for Stable<K> {
+    type Entry = HeapItem<T>;
+
+    #[inline]
+    fn entry(&self, item: T, counter: usize) -> HeapItem<T> {
+        let tie = self.tie_break.key(counter, HeapKey::<K>::key(&item));
+        HeapItem::with_tie(item, counter, tie)
+    }
+
+    #[inline]
+    fn beats(&self, worst: &HeapItem<T>, counter: usize, item: Option<&T>) -> bool {
+        let tie = match item {
+            Some(item) => Some(self.tie_break.key(counter, HeapKey::<K>::key(item))),
+            None => self.tie_break.counter_key(counter),
+        };
+        // A new item always has a bigger counter than the stored ones and therefore
+        // wins if the tie keys are equal too. Without a known key equal items are assumed
+        // to be accepted
+        tie.is_none_or(|tie| tie >= worst.tie)
+    }
+}
+
+/// Keeps track of the positions of a heap's items
+pub trait Positions<E> {
+    /// Remembers that `entry` is stored at `pos`
+    fn set(&mut self, entry: &E, pos: usize);
+
+    /// Called after `entry` got evicted for its rank
+    fn evicted(&mut self, entry: &E);
+
+    /// Called after `entry` got removed explicitly
+    fn removed(&mut self, entry: &E);
+}
+
+/// Decides whether a container keeps equal items
+pub trait Dedupe<T, E>: Positions<E> {
+    /// Returns `Some` if an item equal to `item` got pushed before, along with the position
+    /// of the stored one unless it got evicted already
+    fn duplicate(&self, item: &T) -> Option<Option<usize>>;
+
+    /// Returns the position of a stored item equal to `item`
+    fn position(&self, entries: &[E], item: &T) -> Option<usize>;
+
+    /// Like `position` but also forgets `item`, so it can be inserted again
+    fn forget(&mut self, entries: &[E], item: &T) -> Option<usize>;
+
+    /// Called after `item` got rejected for its rank
+    fn rejected(&mut self, item: &T);
+
+    /// Allocates space for remembering `additional` more items upfront
+    fn reserve(&mut self, additional: usize);
+
+    /// Reserves space for remembering one more item
+    fn try_reserve(&mut self) -> Result<(), TryReserveError>;
+
+    /// Remembers the items remembered by `other`
+    fn absorb(&mut self, other: Self)
+    where
+        Self: Sized;
+}
+
+/// Dedupe policy keeping equal items
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Duplicates;
+
+impl<E> Positions<E> for Duplicates {
+    #[inline]
+    fn set(&mut self, _entry: &E, _pos: usize) {}
+
+    #[inline]
+    fn evicted(&mut self, _entry: &E) {}
+
+    #[inline]
+    fn removed(&mut self, _entry: &E) {}
+}
+
+impl<T: PartialEq, E: HeapKey<T>> Dedupe<T, E> for Duplicates {
+    #[inline]
+    fn duplicate(&self, _item: &T) -> Option<Option<usize>> {
+        None
+    }
+
+    #[inline]
+    fn position(&self, entries: &[E], item: &T) -> Option<usize> {
+        entries.iter().position(|i| HeapKey::<T>::key(i) == item)
+    }
+
+    #[inline]
+    fn forget(&mut self, entries: &[E], item: &T) -> Option<usize> {
+        Dedupe::<T, E>::position(self, entries, item)
+    }
+
+    #[inline]
+    fn rejected(&mut self, _item: &T) {}
+
+    #[inline]
+    fn reserve(&mut self, _additional: usize) {}
+
+    #[inline]
+    fn try_reserve(&mut self) -> Result<(), TryReserveError> {
+        Ok(())
+    }
+
+    #[inline]
+    fn absorb(&mut self, _other: Self) {}
+}
+
+/// Dedupe policy keeping only one item per key `K`, which is the item itself unless the
+/// container got created by `unique_by`. Keys get hashed using `S` and are remembered
+/// according to a `DedupPolicy`
+pub struct Unique<K, S = RandomState> {
+    /// Position of every remembered key within the heap, `None` if it isn't stored anymore
+    positions: HashMap<K, Option<usize>, S>,
+    pub(crate) policy: DedupPolicy,
+}
+
+impl<K> Unique<K> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_hasher(DedupPolicy::Seen, RandomState::new())
+    }
+}
+
+impl<K> Default for Unique<K> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, S> Unique<K, S> {
+    /// Creates a policy remembering keys according to `policy` and hashing them using
+    /// `hash_builder`
+    #[inline]
+    pub fn with_hasher(policy: DedupPolicy, hash_builder: S) -> Self {
+        Self {
+            positions: HashMap::with_hasher(hash_builder),
+            policy,
+        }
+    }
+
+    /// Like `with_hasher` but allocates space for `keys` keys upfront
+    #[inline]
+    pub fn with_capacity_and_hasher(keys: usize, policy: DedupPolicy, hash_builder: S) -> Self {
+        Self {
+            positions: HashMap::with_capacity_and_hasher(keys, hash_builder),
+            policy,
+        }
+    }
+
+    /// Returns the policy deciding which keys are remembered
+    #[inline]
+    pub fn policy(&self) -> DedupPolicy {
+        self.policy
+    }
+}
+
+impl<K: Hash + Eq, S: BuildHasher> Unique<K, S> {
+    /// Returns `true` if `key` is remembered
+    #[inline]
+    pub(crate) fn seen(&self, key: &K) -> bool {
+        self.positions.contains_key(key)
+    }
+
+    /// Returns the position of the stored item with `key`
+    #[inline]
+    pub(crate) fn get(&self, key: &K) -> Option<usize> {
+        self.positions.get(key).copied().flatten()
+    }
+
+    /// Remembers `key` as seen without storing an item for it, unless only the keys of
+    /// stored items get remembered
+    #[inline]
+    pub(crate) fn remember(&mut self, key: K) {
+        if self.policy == DedupPolicy::Seen {
+            self.positions.entry(key).or_insert(None);
+        }
+    }
+
+    /// Forgets `key` and returns the position of its item if it's stored
+    #[inline]
+    pub(crate) fn forget(&mut self, key: &K) -> Option<usize> {
+        self.positions.remove(key).flatten()
+    }
+
+    /// Reserves space for remembering one more key
+    #[inline]
+    pub(crate) fn try_reserve_key(&mut self) -> Result<(), TryReserveError> {
+        self.positions.try_reserve(1)
+    }
+
+    /// Returns all remembered keys
+    #[inline]
+    pub(crate) fn into_keys(self) -> impl Iterator<Item = K> {
+        self.positions.into_keys()
+    }
+}
+
+impl<K, S, E> Positions<E> for Unique<K, S>
+where
+    K: Hash + Eq + Clone,
+    S: BuildHasher,
+    E: HeapKey<K>,
+{
+    #[inline]
+    fn set(&mut self, entry: &E, pos: usize) {
+        let key = HeapKey::<K>::key(entry);
+        match self.positions.get_mut(key) {
+            Some(p) => *p = Some(pos),
+            None => {
+                self.positions.insert(key.clone(), Some(pos));
+            }
+        }
+    }
+
+    #[inline]
+    fn evicted(&mut self, entry: &E) {
+        match self.policy {
+            DedupPolicy::Seen => {
+                if let Some(p) = self.positions.get_mut(HeapKey::<K>::key(entry)) {
+                    *p = None;
+                }
+            }
+            DedupPolicy::Retained => {
+                self.positions.remove(HeapKey::<K>::key(entry));
+            }
+        }
+    }
+
+    #[inline]
+    fn removed(&mut self, entry: &E) {
+        self.positions.remove(HeapKey::<K>::key(entry));
+    }
+}
+
+impl<T, E, K, S> Dedupe<T, E> for Unique<K, S>
+where
+    T: HeapKey<K>,
+    E: HeapKey<K>,
+    K: Hash + Eq + Clone,
+    S: BuildHasher,
+{
+    #[inline]
+    fn duplicate(&self, item: &T) -> Option<Option<usize>> {
+        self.positions.get(HeapKey::<K>::key(item)).copied()
+    }
+
+    #[inline]
+    fn position(&self, _entries: &[E], item: &T) -> Option<usize> {
+        self.get(HeapKey::<K>::key(item))
+    }
+
+    #[inline]
+    fn forget(&mut self, _entries: &[E], item: &T) -> Option<usize> {
+        Unique::forget(self, HeapKey::<K>::key(item))
+    }
+
+    #[inline]
+    fn rejected(&mut self, item: &T) {
+        self.remember(HeapKey::<K>::key(item).clone());
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.positions.reserve(additional);
+    }
+
+    #[inline]
+    fn try_reserve(&mut self) -> Result<(), TryReserveError> {
+        self.try_reserve_key()
+    }
+
+    #[inline]
+    fn absorb(&mut self, other: Self) {
+        for key in other.into_keys() {
+            self.remember(key);
+        }
+    }
+}
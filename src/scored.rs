@@ -0,0 +1,208 @@
+use crate::{
+    by::{Keyed, UniqueKeyed},
+    error::Result,
+    PrioContainer, PrioContainerMax, StablePrioContainer, StablePrioContainerMax,
+    StableUniquePrioContainer, StableUniquePrioContainerMax, UniquePrioContainer,
+    UniquePrioContainerMax,
+};
+use std::hash::Hash;
+
+/// Container storing values next to a score. Only the scores get compared, so values don't
+/// need to implement `Ord`. Use one of the type aliases below to create one
+pub struct Scored<C> {
+    container: C,
+}
+
+/// Keeps the values with the `capacity` smallest scores
+pub type ScoredPrioContainer<S, V> = Scored<PrioContainer<Keyed<S, V>>>;
+
+/// Keeps the values with the `capacity` biggest scores
+pub type ScoredPrioContainerMax<S, V> = Scored<PrioContainerMax<Keyed<S, V>>>;
+
+/// Keeps the values with the `capacity` smallest scores. Values with equal scores are
+/// returned in inserted order
+pub type StableScoredPrioContainer<S, V> = Scored<StablePrioContainer<Keyed<S, V>>>;
+
+/// Keeps the values with the `capacity` biggest scores. Values with equal scores are
+/// returned in inserted order
+pub type StableScoredPrioContainerMax<S, V> = Scored<StablePrioContainerMax<Keyed<S, V>>>;
+
+/// Keeps the distinct values with the `capacity` smallest scores. A value inserted again
+/// with a better score replaces the stored one
+pub type UniqueScoredPrioContainer<S, V> = Scored<UniquePrioContainer<UniqueKeyed<S, V>>>;
+
+/// Keeps the distinct values with the `capacity` biggest scores. A value inserted again
+/// with a better score replaces the stored one
+pub type UniqueScoredPrioContainerMax<S, V> = Scored<UniquePrioContainerMax<UniqueKeyed<S, V>>>;
+
+/// Keeps the distinct values with the `capacity` smallest scores. Values with equal scores
+/// are returned in inserted order
+pub type StableUniqueScoredPrioContainer<S, V> =
+    Scored<StableUniquePrioContainer<UniqueKeyed<S, V>>>;
+
+/// Keeps the distinct values with the `capacity` biggest scores. Values with equal scores
+/// are returned in inserted order
+pub type StableUniqueScoredPrioContainerMax<S, V> =
+    Scored<StableUniquePrioContainerMax<UniqueKeyed<S, V>>>;
+
+impl<C> Scored<C> {
+    /// Returns the wrapped prio container
+    #[inline]
+    pub fn inner(&self) -> &C {
+        &self.container
+    }
+}
+
+macro_rules! impl_scored {
+    (
+        $container:ident,
+        $keyed:ident,
+        [$($v_bound:tt)*],
+        [$($s_bound:tt)*],
+        |$out:ident| $unwrap:expr
+    ) => {
+        impl<S: Ord $($s_bound)*, V $($v_bound)*> Scored<$container<$keyed<S, V>>> {
+            /// Creates a new container keeping `capacity` values
+            ///
+            /// # Panics
+            /// Panics if `capacity` is zero
+            #[inline]
+            pub fn new(capacity: usize) -> Self {
+                let container = $container::new(capacity);
+                Self { container }
+            }
+
+            /// Creates a new container keeping `capacity` values. Returns an error instead of
+            /// panicking if `capacity` is zero
+            #[inline]
+            pub fn try_new(capacity: usize) -> Result<Self> {
+                let container = $container::try_new(capacity)?;
+                Ok(Self { container })
+            }
+
+            /// Inserts `value` with the given `score`
+            #[inline]
+            pub fn insert(&mut self, score: S, value: V) -> bool {
+                self.container.insert($keyed::new(score, value))
+            }
+
+            /// Like `insert` but returns an error if allocating memory for the value fails
+            #[inline]
+            pub fn try_insert(&mut self, score: S, value: V) -> Result<bool> {
+                self.container.try_insert($keyed::new(score, value))
+            }
+
+            /// Returns the worst score currently stored once the container is full
+            #[inline]
+            pub fn threshold(&self) -> Option<&S> {
+                self.container.threshold().map(|i| i.key())
+            }
+
+            /// Returns `true` if a value with `score` would currently be accepted by `insert`
+            #[inline]
+            pub fn would_accept(&self, score: &S) -> bool {
+                self.container.would_accept_by(|worst| score.cmp(worst.key()))
+            }
+
+            /// Merges `other` into the container, keeping the best values of both
+            #[inline]
+            pub fn merge(&mut self, other: Self) {
+                self.container.merge(other.container)
+            }
+
+            /// Removes all values for which `f` returns `false`
+            #[inline]
+            pub fn retain<F: FnMut(&S, &V) -> bool>(&mut self, mut f: F) {
+                self.container.retain(|i| f(i.key(), i))
+            }
+
+            /// Returns an iterator over the stored scores and values sorted from best to worst
+            /// without consuming the container
+            #[inline]
+            pub fn iter_sorted(&self) -> impl Iterator<Item = (&S, &V)> {
+                self.container.sorted_refs().map(|i| (i.key(), &**i))
+            }
+
+            #[inline]
+            pub fn len(&self) -> usize {
+                self.container.len()
+            }
+
+            #[inline]
+            pub fn is_empty(&self) -> bool {
+                self.container.is_empty()
+            }
+
+            #[inline]
+            pub fn capacity(&self) -> usize {
+                self.container.capacity()
+            }
+
+            #[inline]
+            pub fn total_pushed(&self) -> usize {
+                self.container.total_pushed()
+            }
+
+            /// Returns the stored items in no particular order without sorting them
+            #[inline]
+            pub fn into_vec(self) -> Vec<(S, V)> {
+                self.container.into_vec().into_iter().map(|$out| $unwrap).collect()
+            }
+
+            /// Returns an iterator over the stored items in no particular order
+            #[inline]
+            pub fn into_unsorted_iter(self) -> std::vec::IntoIter<(S, V)> {
+                self.into_vec().into_iter()
+            }
+        }
+
+        impl<S: Ord $($s_bound)*, V $($v_bound)*> Extend<(S, V)>
+            for Scored<$container<$keyed<S, V>>>
+        {
+            #[inline]
+            fn extend<I: IntoIterator<Item = (S, V)>>(&mut self, iter: I) {
+                for (score, value) in iter {
+                    self.insert(score, value);
+                }
+            }
+        }
+
+        impl<S: Ord $($s_bound)*, V $($v_bound)*> IntoIterator
+            for Scored<$container<$keyed<S, V>>>
+        {
+            type Item = (S, V);
+
+            type IntoIter = std::iter::Map<
+                <$container<$keyed<S, V>> as IntoIterator>::IntoIter,
+                fn(<$container<$keyed<S, V>> as IntoIterator>::Item) -> (S, V),
+            >;
+
+            #[inline]
+            fn into_iter(self) -> Self::IntoIter {
+                self.container.into_iter().map(|$out| $unwrap)
+            }
+        }
+    };
+}
+
+impl_scored! { PrioContainer, Keyed, [], [], |i| i.into_parts() }
+impl_scored! { PrioContainerMax, Keyed, [], [], |i| i.into_parts() }
+impl_scored! { StablePrioContainer, Keyed, [], [], |i| i.into_parts() }
+impl_scored! { StablePrioContainerMax, Keyed, [], [], |i| i.into_parts() }
+
+impl_scored! {
+    UniquePrioContainer, UniqueKeyed,
+    [: Hash + Eq + Clone], [+ Clone], |i| i.into_parts()
+}
+impl_scored! {
+    UniquePrioContainerMax, UniqueKeyed,
+    [: Hash + Eq + Clone], [+ Clone], |i| i.into_parts()
+}
+impl_scored! {
+    StableUniquePrioContainer, UniqueKeyed,
+    [: Hash + Eq + Clone], [+ Clone], |i| i.into_parts()
+}
+impl_scored! {
+    StableUniquePrioContainerMax, UniqueKeyed,
+    [: Hash + Eq + Clone], [+ Clone], |i| i.into_parts()
+}
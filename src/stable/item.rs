@@ -6,6 +6,8 @@ use std::{
 pub struct HeapItem<T> {
     pub inner: T,
     pub counter: usize,
+    /// Decides the order of equal items, bigger keys rank better
+    pub(crate) tie: u64,
 }
 
 impl<T: Ord> HeapItem<T> {
@@ -14,6 +16,7 @@ impl<T: Ord> HeapItem<T> {
         HeapItem {
             inner,
             counter: pos,
+            tie: pos as u64,
         }
     }
 
@@ -21,24 +24,36 @@ impl<T: Ord> HeapItem<T> {
     pub fn into_inner(self) -> T {
         self.inner
     }
+}
+
+impl<T> HeapItem<T> {
+    #[inline]
+    pub(crate) fn with_tie(inner: T, pos: usize, tie: u64) -> Self {
+        HeapItem {
+            inner,
+            counter: pos,
+            tie,
+        }
+    }
 
-    /*
-    /// Get a reference to the heap item's inner.
     #[inline]
     pub fn inner(&self) -> &T {
         &self.inner
     }
 
+    /// Compares the ranks of two items which compare equal, the better one compares smaller
     #[inline]
-    pub fn inner_mut(&mut self) -> &mut T {
-        &mut self.inner
+    pub(crate) fn cmp_tie(&self, other: &Self) -> Ordering {
+        let tie = self.tie.cmp(&other.tie).reverse();
+        tie.then_with(|| self.counter.cmp(&other.counter).reverse())
     }
 
-    /// Get a mutable reference to the heap item's counter.
-    pub fn counter_mut(&mut self) -> &mut usize {
-        &mut self.counter
+    /// Returns the item's insertion sequence, the container's `total_pushed` at the time the
+    /// item got pushed
+    #[inline]
+    pub fn counter(&self) -> usize {
+        self.counter
     }
-    */
 }
 
 impl<T> AsRef<T> for HeapItem<T> {
@@ -67,21 +82,21 @@ impl<T> DerefMut for HeapItem<T> {
 impl<T: Ord + PartialEq> PartialEq for HeapItem<T> {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
-        self.counter == other.counter && self.inner == other.inner
+        self.counter == other.counter && self.tie == other.tie && self.inner == other.inner
     }
 }
 
 impl<T: Ord + PartialEq> Eq for HeapItem<T> {}
 
+#[allow(clippy::non_canonical_partial_ord_impl)]
 impl<T: Ord + PartialEq> PartialOrd for HeapItem<T> {
     #[inline]
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        let cmp = self.inner.cmp(&other.inner);
-        if cmp == Ordering::Equal {
-            return Some(self.counter.cmp(&other.counter).reverse());
-        }
-
-        Some(cmp)
+        Some(
+            self.inner
+                .cmp(&other.inner)
+                .then_with(|| self.cmp_tie(other)),
+        )
     }
 }
 
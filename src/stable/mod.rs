@@ -1,133 +1,95 @@
 pub mod item;
 pub mod max;
 
-use self::item::HeapItem;
-use crate::iter::StableHeapIter;
-use std::collections::BinaryHeap;
-
-/// A stable priority container. This means equal elements are returned in inserted order
-pub struct StablePrioContainer<T> {
-    pub(crate) heap: BinaryHeap<HeapItem<T>>,
-    pub(crate) total_pushed: usize,
-    pub(crate) capacity: usize,
+use crate::{
+    policy::{Duplicates, Min, Stable},
+    TopK,
+};
+use std::fmt;
+
+/// Decides how a stable container ranks items which compare equal
+pub enum TieBreak<T> {
+    /// The item pushed last ranks best
+    Lifo,
+    /// The item pushed first ranks best
+    Fifo,
+    /// Equal items rank in a random order which only depends on the seed and the order in
+    /// which they got pushed
+    Random(u64),
+    /// The item with the smallest sequence number, like a timestamp or row ID, ranks best.
+    /// Items with the same sequence number rank like `Lifo`
+    Sequence(Box<dyn Fn(&T) -> u64 + Send + Sync>),
 }
 
-impl<T: Ord> StablePrioContainer<T> {
-    /// Creates a new StablePrioContainer with given max items. This value must not be smaller than 1
-    ///
-    /// # Panics
-    /// Panics if `capacity` is 0
-    pub fn new(capacity: usize) -> Self {
-        assert!(capacity > 0);
-        let heap = BinaryHeap::new();
-
-        StablePrioContainer {
-            heap,
-            total_pushed: 0,
-            capacity,
-        }
-    }
-
-    /// Create a new StablePrioContainer with given preallocated size. `capacity` must not be smaller than 1
-    ///
-    /// # Panics
-    /// Panics if `capacity` is 0
-    pub fn new_allocated(capacity: usize, alloc_size: usize) -> Self {
-        assert!(capacity > 0);
-
-        // We'll never allocate more items than `capacity` so prevent stupid input
-        // doin stupid things
-        let alloc_size = alloc_size.min(capacity);
-
-        let heap = BinaryHeap::with_capacity(alloc_size);
-
-        StablePrioContainer {
-            heap,
-            total_pushed: 0,
-            capacity,
-        }
-    }
-
-    /// Pushes a new element into the PrioContainer
-    pub fn insert(&mut self, item: T) -> bool {
-        self.total_pushed += 1;
-        if self.heap.len() < self.capacity {
-            self.heap.push(HeapItem::new(item, self.total_pushed));
-            return true;
-        }
-
-        let new_item = HeapItem::new(item, self.total_pushed);
-
-        // Safety:
-        //
-        // heap.len() >= n without elements is impossible for n>0 which is enforced in `PrioContainer::new()`
-        let min_item = unsafe { self.heap.peek().unwrap_unchecked() };
-        if *min_item <= new_item {
-            return false;
-        }
-
-        *unsafe { self.heap.peek_mut().unwrap_unchecked() } = new_item;
-
-        true
-    }
-
+impl<T> TieBreak<T> {
+    /// Breaks ties by the sequence number returned by `f`
     #[inline]
-    pub fn inc_push(&mut self, delta: usize) {
-        self.total_pushed += delta;
+    pub fn sequence<F: Fn(&T) -> u64 + Send + Sync + 'static>(f: F) -> Self {
+        Self::Sequence(Box::new(f))
     }
 
+    /// Returns the tie key of `item` pushed as `counter`-th item
     #[inline]
-    pub fn contains(&self, item: &T) -> bool {
-        self.heap.iter().any(|i| *i.as_ref() == *item)
-    }
-
-    /// Return a sorted vec of the prio container
-    #[inline]
-    pub fn into_sorted_vec(self) -> Vec<T> {
-        self.into_iter().collect()
-    }
-}
-
-impl<T> StablePrioContainer<T> {
-    /// Returns the amount of items currently stored in the PrioContainer
-    #[inline]
-    pub fn len(&self) -> usize {
-        self.heap.len()
-    }
-
-    /// Returns `true` if no items have been pushed onto the PrioContainer
-    #[inline]
-    pub fn is_empty(&self) -> bool {
-        self.len() == 0
+    pub(crate) fn key(&self, counter: usize, item: &T) -> u64 {
+        match self {
+            Self::Sequence(f) => !f(item),
+            _ => self.counter_key(counter).unwrap_or_default(),
+        }
     }
 
+    /// Returns the tie key of the `counter`-th pushed item or `None` if it depends on the item
     #[inline]
-    pub fn capacity(&self) -> usize {
-        self.capacity
+    pub(crate) fn counter_key(&self, counter: usize) -> Option<u64> {
+        match self {
+            Self::Lifo => Some(counter as u64),
+            Self::Fifo => Some(!(counter as u64)),
+            Self::Random(seed) => Some(mix(seed.wrapping_add(counter as u64))),
+            Self::Sequence(_) => None,
+        }
     }
 
-    #[inline]
-    pub fn total_pushed(&self) -> usize {
-        self.total_pushed
+    /// Turns the policy into one for items wrapping a `T`, which `get` returns
+    pub(crate) fn map<U>(self, get: fn(&U) -> &T) -> TieBreak<U>
+    where
+        T: 'static,
+        U: 'static,
+    {
+        match self {
+            Self::Lifo => TieBreak::Lifo,
+            Self::Fifo => TieBreak::Fifo,
+            Self::Random(seed) => TieBreak::Random(seed),
+            Self::Sequence(f) => TieBreak::sequence(move |i: &U| f(get(i))),
+        }
     }
 }
 
-impl<T: Ord> IntoIterator for StablePrioContainer<T> {
-    type Item = T;
-
-    type IntoIter = StableHeapIter<T>;
-
+impl<T> Default for TieBreak<T> {
     #[inline]
-    fn into_iter(self) -> Self::IntoIter {
-        StableHeapIter::new(self.heap)
+    fn default() -> Self {
+        Self::Lifo
     }
 }
 
-impl<T: Ord> Extend<T> for StablePrioContainer<T> {
-    #[inline]
-    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
-        for i in iter {
-            self.insert(i);
+impl<T> fmt::Debug for TieBreak<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Lifo => write!(f, "Lifo"),
+            Self::Fifo => write!(f, "Fifo"),
+            Self::Random(seed) => write!(f, "Random({seed})"),
+            Self::Sequence(_) => write!(f, "Sequence"),
         }
     }
 }
+
+/// Scrambles `x` so consecutive inputs give unrelated outputs (SplitMix64)
+#[inline]
+fn mix(x: u64) -> u64 {
+    let mut z = x.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// A stable priority container. This means equal elements are returned in inserted order.
+/// The tie break looks at the `K` each item wraps, which is the item itself by default
+pub type StablePrioContainer<T, K = T> = TopK<T, Min, Stable<K>, Duplicates>;
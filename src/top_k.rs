@@ -0,0 +1,703 @@
+use crate::{
+    builder::Builder,
+    error::{Error, Result},
+    heap::{Heap, HeapKey},
+    iter::{self, IntoIter, ItemAt, SortedIter},
+    policy::{Dedupe, Duplicates, Entry, Min, Order, Stable, Ties, Unique, Unstable},
+    stable::{item::HeapItem, TieBreak},
+    DedupPolicy, DuplicatePolicy,
+};
+use std::{
+    cmp::Ordering,
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hash},
+    marker::PhantomData,
+    sync::OnceLock,
+};
+
+/// Priority container storing max `capacity` amount of items. Can be used to find the `n`
+/// best items within an iterator or a set of items that implement `Ord`. The order policy
+/// `O` decides whether the smallest or the biggest items are best, the tie policy `B` how
+/// equal items rank among each other and the dedupe policy `D` whether equal items are kept.
+/// The prio containers of this crate are aliases of it, apart from the fingerprint based ones
+pub struct TopK<T, O = Min, B: Ties<T> = Unstable, D = Duplicates> {
+    heap: Heap<B::Entry, D>,
+    ties: B,
+    /// Max amount of items that will be returned in the end
+    capacity: usize,
+    total_pushed: usize,
+    /// Whether an item got evicted or rejected for its rank since the container was created
+    dropped: bool,
+    /// Positions of the heap's items sorted from best to worst. Gets reset whenever the heap changes
+    sorted: OnceLock<Vec<usize>>,
+    /// Resolves a pushed item against an equal stored one, only used by unique containers
+    on_duplicate: DuplicatePolicy<T>,
+    order: PhantomData<fn() -> O>,
+}
+
+impl<T> TopK<T> {
+    /// Returns a builder for a container keeping the smallest items with duplicates in no
+    /// particular order
+    #[inline]
+    pub fn builder() -> Builder<T> {
+        Builder::new()
+    }
+}
+
+impl<T, O, B, D> TopK<T, O, B, D>
+where
+    T: Ord,
+    O: Order,
+    B: Ties<T> + Default,
+    D: Dedupe<T, B::Entry> + Default,
+{
+    /// Create a new container with `capacity`
+    ///
+    /// # Panics
+    /// Panics if `capacity` is zero
+    #[inline]
+    pub fn new(capacity: usize) -> Self {
+        Self::with_parts(capacity, 0, B::default(), D::default())
+    }
+
+    /// Create a new container with `capacity`. Returns an error instead of panicking
+    /// if `capacity` is zero
+    #[inline]
+    pub fn try_new(capacity: usize) -> Result<Self> {
+        if capacity == 0 {
+            return Err(Error::ZeroCapacity);
+        }
+        Ok(Self::new(capacity))
+    }
+
+    /// Create a new container with space for `alloc_size` items allocated upfront
+    ///
+    /// # Panics
+    /// Panics if `capacity` is zero
+    #[inline]
+    pub fn new_allocated(capacity: usize, alloc_size: usize) -> Self {
+        let mut dedupe = D::default();
+        dedupe.reserve(alloc_size);
+        Self::with_parts(capacity, alloc_size, B::default(), dedupe)
+    }
+}
+
+impl<T, O, B, D> TopK<T, O, B, D>
+where
+    T: Ord,
+    O: Order,
+    B: Ties<T>,
+    D: Dedupe<T, B::Entry>,
+{
+    /// Creates a container with `alloc_size` preallocated slots from its policies
+    ///
+    /// # Panics
+    /// Panics if `capacity` is zero
+    pub(crate) fn with_parts(capacity: usize, alloc_size: usize, ties: B, dedupe: D) -> Self {
+        if capacity == 0 {
+            panic!("Capacity can't be zero");
+        }
+
+        // We'll never store more items than `capacity`
+        let heap = Heap::with_capacity(alloc_size.min(capacity), dedupe);
+
+        Self {
+            heap,
+            ties,
+            capacity,
+            total_pushed: 0,
+            dropped: false,
+            sorted: OnceLock::new(),
+            on_duplicate: DuplicatePolicy::KeepBest,
+            order: PhantomData,
+        }
+    }
+
+    /// Inserts a new item into the container. An item equal to a stored one gets resolved
+    /// according to the container's `DuplicatePolicy` if it dedupes its items
+    pub fn insert(&mut self, item: T) -> bool {
+        self.total_pushed += 1;
+        if let Some(pos) = self.heap.index.duplicate(&item) {
+            if let Some(pos) = pos {
+                self.replace_eq(pos, item);
+            }
+            return false;
+        }
+
+        let entry = self.ties.entry(item, self.total_pushed);
+        self.push_heap(entry)
+    }
+
+    /// Like `insert` but returns an error if allocating memory for the item fails
+    #[inline]
+    pub fn try_insert(&mut self, item: T) -> Result<bool> {
+        if self.heap.index.duplicate(&item).is_none() {
+            self.heap.index.try_reserve()?;
+            if self.heap.len() < self.capacity {
+                self.heap.try_reserve()?;
+            }
+        }
+        Ok(self.insert(item))
+    }
+
+    /// Merges `other` into the container, keeping the best items of both. Items of `other`
+    /// are treated as if they were pushed after all items of `self`, so merging shards in a
+    /// fixed order always gives the same result as pushing all of them into one container.
+    /// Items pushed into both containers are only kept once if the container dedupes its items
+    pub fn merge(&mut self, other: Self) {
+        let offset = self.total_pushed;
+        self.total_pushed += other.total_pushed;
+        self.dropped |= other.dropped;
+
+        let (entries, seen) = other.heap.into_parts();
+        for entry in entries {
+            let counter = entry.counter() + offset;
+            let item = entry.into_item();
+            match self.heap.index.duplicate(&item) {
+                Some(Some(pos)) => self.replace_eq(pos, item),
+                Some(None) => {}
+                None => {
+                    let entry = self.ties.entry(item, counter);
+                    self.push_heap(entry);
+                }
+            }
+        }
+
+        self.heap.index.absorb(seen);
+    }
+
+    /// Removes all items for which `f` returns `false`. Unique containers forget removed
+    /// items, so they can be inserted again
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        if self.heap.retain(|i| f(entry_item(i)), Self::rank) {
+            self.sorted.take();
+        }
+    }
+
+    /// Removes `item` from the container. Returns `true` if it was stored. Unique containers
+    /// forget `item`, so it can be inserted again
+    pub fn remove(&mut self, item: &T) -> bool {
+        if self.heap.remove_item(item, Self::rank).is_none() {
+            return false;
+        }
+
+        self.sorted.take();
+        true
+    }
+
+    /// Removes the first stored item for which `f` returns `true`. Unique containers forget
+    /// it, so it can be inserted again
+    pub(crate) fn remove_by<F: FnMut(&T) -> bool>(&mut self, mut f: F) -> bool {
+        if self
+            .heap
+            .remove_where(|i| f(entry_item(i)), Self::rank)
+            .is_none()
+        {
+            return false;
+        }
+
+        self.sorted.take();
+        true
+    }
+
+    /// Changes the capacity of the container. Shrinking evicts the worst items, which are
+    /// treated according to the `DedupPolicy` of unique containers just like items evicted by
+    /// `insert`. Returns `true` if the container grew after it already dropped items, in which
+    /// case it may be missing items that would fit into the new capacity
+    pub fn set_capacity(&mut self, capacity: usize) -> Result<bool> {
+        if capacity == 0 {
+            return Err(Error::ZeroCapacity);
+        }
+
+        let grown = capacity > self.capacity;
+        self.capacity = capacity;
+
+        while self.heap.len() > capacity {
+            self.heap.pop(Self::rank);
+            self.sorted.take();
+            self.dropped = true;
+        }
+
+        Ok(grown && self.dropped)
+    }
+
+    /// Pushes an already counted item onto the heap if it ranks good enough
+    fn push_heap(&mut self, entry: B::Entry) -> bool {
+        if self.heap.len() < self.capacity {
+            self.heap.push(entry, Self::rank);
+            self.sorted.take();
+            return true;
+        }
+
+        // Either the new item or the current worst one gets dropped
+        self.dropped = true;
+
+        // Safety:
+        //
+        // heap.len() >= n without elements is impossible for n>0 which is enforced in `with_parts()`
+        let worst = unsafe { self.heap.peek().unwrap_unchecked() };
+        if Self::rank(worst, &entry).is_le() {
+            self.heap.index.rejected(entry_item(&entry));
+            return false;
+        }
+
+        self.heap.replace_top(entry, Self::rank);
+        self.sorted.take();
+        true
+    }
+
+    /// Returns the worst item currently stored once the container is full. Items ranking
+    /// worse than the threshold will be rejected by `insert`, equal items only get accepted
+    /// by stable containers if they win the tie break
+    #[inline]
+    pub fn threshold(&self) -> Option<&T> {
+        if self.heap.len() < self.capacity {
+            return None;
+        }
+        self.heap.peek().map(entry_item)
+    }
+
+    /// Returns `true` if `item` ranks good enough to currently be accepted by `insert`.
+    /// This doesn't check whether `item` is a duplicate
+    #[inline]
+    pub fn would_accept(&self, item: &T) -> bool {
+        self.would_accept_tie(|worst| item.cmp(worst), Some(item))
+    }
+
+    /// Returns an iterator over the stored items sorted from best to worst without consuming
+    /// the container. The order is cached until the container changes
+    #[inline]
+    pub fn iter_sorted(&self) -> SortedIter<'_, T> {
+        SortedIter::new(self.sorted_positions(), self)
+    }
+
+    /// Returns the stored items sorted from best to worst without consuming the container
+    #[inline]
+    pub fn to_sorted_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.sorted_refs().cloned().collect()
+    }
+
+    #[inline]
+    pub(crate) fn sorted_refs(&self) -> impl Iterator<Item = &T> {
+        let items = self.heap.as_slice();
+        let order = self.sorted_positions();
+        order.iter().map(move |i| entry_item(&items[*i]))
+    }
+
+    /// Returns the heap positions of the stored items sorted from best to worst
+    #[inline]
+    pub(crate) fn sorted_positions(&self) -> &[usize] {
+        self.sorted
+            .get_or_init(|| iter::sorted_order(self.heap.as_slice(), Self::rank))
+    }
+
+    /// Returns `true` if an item would currently be accepted. `f` compares the
+    /// item against the current threshold by their natural order
+    #[inline]
+    pub(crate) fn would_accept_by<F: FnOnce(&T) -> Ordering>(&self, f: F) -> bool {
+        self.would_accept_tie(f, None)
+    }
+
+    /// Like `would_accept_by` but equal items are decided by the tie policy, which looks at
+    /// `item` if it's known
+    #[inline]
+    fn would_accept_tie<F: FnOnce(&T) -> Ordering>(&self, f: F, item: Option<&T>) -> bool {
+        if self.heap.len() < self.capacity {
+            return true;
+        }
+        let Some(worst) = self.heap.peek() else {
+            return true;
+        };
+
+        match O::rank(f(entry_item(worst))) {
+            Ordering::Less => true,
+            Ordering::Greater => false,
+            Ordering::Equal => self.ties.beats(worst, self.total_pushed + 1, item),
+        }
+    }
+
+    /// Inserts the item built by `f` if `bound` would currently be accepted. `bound` has
+    /// to rank at least as good as the built item. This allows skipping the construction
+    /// of items which can't make it into the container anyway
+    #[inline]
+    pub fn insert_with<F: FnOnce() -> T>(&mut self, bound: &T, f: F) -> bool {
+        self.insert_with_by(|worst| bound.cmp(worst), f)
+    }
+
+    /// Like `insert_with` but `bound` compares the bound against the current threshold
+    #[inline]
+    pub(crate) fn insert_with_by<G, F>(&mut self, bound: G, f: F) -> bool
+    where
+        G: FnOnce(&T) -> Ordering,
+        F: FnOnce() -> T,
+    {
+        if !self.would_accept_by(bound) {
+            self.total_pushed += 1;
+            self.dropped = true;
+            return false;
+        }
+
+        self.insert(f())
+    }
+
+    #[inline]
+    pub fn inc_push(&mut self, delta: usize) {
+        self.total_pushed += delta;
+    }
+
+    #[inline]
+    pub fn contains(&self, item: &T) -> bool {
+        let entries = self.heap.as_slice();
+        self.heap.index.position(entries, item).is_some()
+    }
+
+    /// Return a sorted vec of the prio container
+    #[inline]
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        self.into_iter().best_first().collect()
+    }
+
+    /// Returns the stored items in no particular order. Unlike `into_sorted_vec` this takes
+    /// O(n) since the items don't get sorted
+    #[inline]
+    pub fn into_vec(self) -> Vec<T> {
+        self.into_iter().into_vec()
+    }
+
+    /// Returns an iterator over the stored items in no particular order
+    #[inline]
+    pub fn into_unsorted_iter(self) -> std::vec::IntoIter<T> {
+        self.into_vec().into_iter()
+    }
+
+    /// Resolves `item` against the equal item stored at `pos` according to the container's
+    /// `DuplicatePolicy`. The stored item's push counter is kept, while a `Sequence` tie key
+    /// gets taken from the item replacing it
+    fn replace_eq(&mut self, pos: usize, item: T) {
+        let old = &self.heap.as_slice()[pos];
+        let counter = old.counter();
+        let ties = &self.ties;
+        match &mut self.on_duplicate {
+            DuplicatePolicy::KeepBest if O::cmp(&item, entry_item(old)).is_lt() => {
+                self.heap
+                    .replace_at(pos, ties.entry(item, counter), Self::rank);
+            }
+            DuplicatePolicy::KeepBest | DuplicatePolicy::KeepFirst => return,
+            DuplicatePolicy::KeepLast => {
+                self.heap
+                    .replace_at(pos, ties.entry(item, counter), Self::rank);
+            }
+            DuplicatePolicy::Combine(f) => self.heap.update(
+                pos,
+                |old| ties.entry(f(old.into_item(), item), counter),
+                Self::rank,
+            ),
+        }
+        self.sorted.take();
+    }
+
+    /// Compares the ranks of two stored items, the better one compares smaller
+    #[inline]
+    fn rank(a: &B::Entry, b: &B::Entry) -> Ordering {
+        a.rank::<O>(b)
+    }
+}
+
+impl<T, O, K, D> TopK<T, O, Stable<K>, D>
+where
+    T: Ord + HeapKey<K>,
+    O: Order,
+    D: Dedupe<T, HeapItem<T>>,
+{
+    /// Creates a new container which ranks equal items according to `tie_break`
+    ///
+    /// # Panics
+    /// Panics if `capacity` is zero
+    #[inline]
+    pub fn with_tie_break(capacity: usize, tie_break: TieBreak<K>) -> Self
+    where
+        D: Default,
+    {
+        Self::with_parts(capacity, 0, Stable::new(tie_break), D::default())
+    }
+
+    /// Changes how equal items rank among each other. Stored items get reordered as if they
+    /// were pushed with the new policy
+    pub fn set_tie_break(&mut self, tie_break: TieBreak<K>) {
+        self.ties = Stable::new(tie_break);
+        let tie_break = &self.ties.tie_break;
+        self.heap.update_all(
+            |i| i.tie = tie_break.key(i.counter, HeapKey::<K>::key(&i.inner)),
+            Self::rank,
+        );
+        self.sorted.take();
+    }
+
+    /// Returns the stored items along with their insertion sequence in no particular order,
+    /// so they can be sorted later
+    #[inline]
+    pub fn into_heap_items(self) -> Vec<HeapItem<T>> {
+        self.into_iter().into_heap_items()
+    }
+}
+
+impl<T, O, B> TopK<T, O, B, Unique<T>>
+where
+    T: Ord + Clone + Hash,
+    O: Order,
+    B: Ties<T> + Default,
+{
+    /// Create a new unique container which dedupes according to `policy`
+    ///
+    /// # Panics
+    /// Panics if `capacity` is zero
+    #[inline]
+    pub fn with_policy(capacity: usize, policy: DedupPolicy) -> Self {
+        Self::keyed(capacity, policy, RandomState::new())
+    }
+}
+
+impl<T, O, B, S> TopK<T, O, B, Unique<T, S>>
+where
+    T: Ord + Clone + Hash,
+    O: Order,
+    B: Ties<T> + Default,
+    S: BuildHasher,
+{
+    /// Create a new unique container which hashes its items using `hash_builder`
+    ///
+    /// # Panics
+    /// Panics if `capacity` is zero
+    #[inline]
+    pub fn with_hasher(capacity: usize, hash_builder: S) -> Self {
+        Self::with_policy_and_hasher(capacity, DedupPolicy::Seen, hash_builder)
+    }
+
+    /// Create a new unique container which dedupes according to `policy` and hashes its items
+    /// using `hash_builder`
+    ///
+    /// # Panics
+    /// Panics if `capacity` is zero
+    #[inline]
+    pub fn with_policy_and_hasher(capacity: usize, policy: DedupPolicy, hash_builder: S) -> Self {
+        Self::keyed(capacity, policy, hash_builder)
+    }
+
+    /// Create a new unique container which hashes its items using `hash_builder` and
+    /// allocates space for `capacity` items upfront
+    ///
+    /// # Panics
+    /// Panics if `capacity` is zero
+    #[inline]
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        let dedupe = Unique::with_capacity_and_hasher(capacity, DedupPolicy::Seen, hash_builder);
+        Self::with_parts(capacity, capacity, B::default(), dedupe)
+    }
+}
+
+impl<T, O, B, K, S> TopK<T, O, B, Unique<K, S>>
+where
+    T: Ord + HeapKey<K>,
+    O: Order,
+    B: Ties<T>,
+    B::Entry: HeapKey<K>,
+    K: Hash + Eq + Clone,
+    S: BuildHasher,
+{
+    /// Creates a container which dedupes its items by their key `K`
+    #[inline]
+    pub(crate) fn keyed(capacity: usize, policy: DedupPolicy, hash_builder: S) -> Self
+    where
+        B: Default,
+    {
+        let dedupe = Unique::with_hasher(policy, hash_builder);
+        Self::with_parts(capacity, 0, B::default(), dedupe)
+    }
+
+    /// Removes the stored item with `key` and forgets the key. Returns `true` if an item
+    /// was stored
+    pub(crate) fn remove_key(&mut self, key: &K) -> bool {
+        if self.heap.forget(key, Self::rank).is_none() {
+            return false;
+        }
+
+        self.sorted.take();
+        true
+    }
+
+    /// Returns the policy used to detect duplicates
+    #[inline]
+    pub fn policy(&self) -> DedupPolicy {
+        self.heap.policy()
+    }
+
+    /// Sets how a pushed item gets resolved against an equal item that is still stored
+    #[inline]
+    pub fn set_duplicate_policy(&mut self, policy: DuplicatePolicy<T>) {
+        self.on_duplicate = policy;
+    }
+}
+
+impl<T, O, B: Ties<T>, D> TopK<T, O, B, D> {
+    /// Returns the amount of items in the container. This value
+    /// is always smaller or equal to `capacity`
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Returns `true` if there is no item in the container
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Get the prio container's capacity
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the total amount of items pushed into the prio container
+    #[inline]
+    pub fn total_pushed(&self) -> usize {
+        self.total_pushed
+    }
+}
+
+/// Returns the item stored as `entry`
+#[inline]
+fn entry_item<T, E: Entry<T>>(entry: &E) -> &T {
+    HeapKey::<T>::key(entry)
+}
+
+impl<T, O, B: Ties<T>, D> ItemAt<T> for TopK<T, O, B, D> {
+    #[inline]
+    fn item_at(&self, pos: usize) -> &T {
+        entry_item(&self.heap.as_slice()[pos])
+    }
+}
+
+impl<T, O, B, D> Extend<T> for TopK<T, O, B, D>
+where
+    T: Ord,
+    O: Order,
+    B: Ties<T>,
+    D: Dedupe<T, B::Entry>,
+{
+    #[inline]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for i in iter {
+            self.insert(i);
+        }
+    }
+}
+
+impl<T: Ord, O: Order, B: Ties<T>, D> IntoIterator for TopK<T, O, B, D> {
+    type Item = T;
+
+    type IntoIter = IntoIter<T, O, B::Entry>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter::new(self.heap.into_parts().0)
+    }
+}
+
+impl<T, O, B, D> TopKContainer<T> for TopK<T, O, B, D>
+where
+    T: Ord,
+    O: Order,
+    B: Ties<T>,
+    D: Dedupe<T, B::Entry>,
+{
+    #[inline]
+    fn insert(&mut self, item: T) -> bool {
+        self.insert(item)
+    }
+
+    #[inline]
+    fn threshold(&self) -> Option<&T> {
+        self.threshold()
+    }
+
+    #[inline]
+    fn would_accept(&self, item: &T) -> bool {
+        self.would_accept(item)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.capacity()
+    }
+
+    #[inline]
+    fn total_pushed(&self) -> usize {
+        self.total_pushed()
+    }
+
+    #[inline]
+    fn iter_sorted(&self) -> SortedIter<'_, T> {
+        self.iter_sorted()
+    }
+
+    #[inline]
+    fn drain_sorted(&mut self) -> Vec<T> {
+        self.sorted.take();
+        let mut entries = self.heap.take();
+        entries.sort_unstable_by(Self::rank);
+        entries.into_iter().map(Entry::into_item).collect()
+    }
+}
+
+/// Common interface of all prio containers. Allows writing code which is generic over the
+/// container variant or picks one at runtime using `Box<dyn TopKContainer<T>>`
+pub trait TopKContainer<T> {
+    /// Inserts a new item into the container. Returns `true` if the item got stored
+    fn insert(&mut self, item: T) -> bool;
+
+    /// Returns the worst item currently stored once the container is full
+    fn threshold(&self) -> Option<&T>;
+
+    /// Returns `true` if `item` would currently be accepted by `insert`
+    fn would_accept(&self, item: &T) -> bool;
+
+    /// Returns the amount of items currently stored in the container
+    fn len(&self) -> usize;
+
+    /// Returns `true` if there is no item in the container
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the max amount of items the container keeps
+    fn capacity(&self) -> usize;
+
+    /// Returns the total amount of items pushed into the container
+    fn total_pushed(&self) -> usize;
+
+    /// Returns an iterator over the stored items sorted from best to worst without
+    /// consuming the container
+    fn iter_sorted(&self) -> SortedIter<'_, T>;
+
+    /// Returns the stored items sorted from best to worst without consuming the container
+    #[inline]
+    fn to_sorted_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.iter_sorted().cloned().collect()
+    }
+
+    /// Removes all stored items and returns them sorted from best to worst
+    fn drain_sorted(&mut self) -> Vec<T>;
+}
@@ -0,0 +1,654 @@
+use super::{DedupPolicy, DuplicatePolicy};
+use crate::{
+    error::{Error, Result},
+    heap::{Heap, HeapKey},
+    iter::{self, IntoIter, ItemAt, SortedIter},
+    policy::{Min, Order, Unique},
+    TopKContainer,
+};
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::RandomState, HashMap},
+    hash::{BuildHasher, BuildHasherDefault, Hash, Hasher},
+    marker::PhantomData,
+    sync::OnceLock,
+};
+
+/// Fingerprint of an item, used by the fingerprint based unique containers to detect
+/// duplicates without storing a copy of each item. Wider fingerprints lower the risk of
+/// two distinct items sharing one
+pub trait Fingerprint: Copy + Hash + Eq {
+    fn of<T: Hash + ?Sized, S: BuildHasher>(item: &T, hash_builder: &S) -> Self;
+}
+
+impl Fingerprint for u64 {
+    #[inline]
+    fn of<T: Hash + ?Sized, S: BuildHasher>(item: &T, hash_builder: &S) -> Self {
+        hash_builder.hash_one(item)
+    }
+}
+
+impl Fingerprint for u128 {
+    #[inline]
+    fn of<T: Hash + ?Sized, S: BuildHasher>(item: &T, hash_builder: &S) -> Self {
+        let low = hash_builder.hash_one(item);
+        let high = hash_builder.hash_one((u8::MAX, item));
+        (u128::from(high) << 64) | u128::from(low)
+    }
+}
+
+/// Hasher for fingerprints, which are hashes already
+#[derive(Default)]
+pub(crate) struct FingerprintHasher(u64);
+
+impl Hasher for FingerprintHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 = self.0.rotate_left(8) ^ u64::from(*b);
+        }
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.0 ^= i;
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.0 ^= i as u64 ^ (i >> 64) as u64;
+    }
+}
+
+/// Item stored next to its fingerprint and the slot it occupies among the items sharing
+/// that fingerprint
+pub(crate) struct Fingerprinted<T, F> {
+    key: (F, u32),
+    pub(crate) item: T,
+}
+
+impl<T, F> HeapKey<(F, u32)> for Fingerprinted<T, F> {
+    #[inline]
+    fn key(&self) -> &(F, u32) {
+        &self.key
+    }
+}
+
+pub(crate) type FingerprintHeap<T, F> =
+    Heap<Fingerprinted<T, F>, Unique<(F, u32), BuildHasherDefault<FingerprintHasher>>>;
+
+/// Where a pushed item belongs among the items sharing its fingerprint
+enum Slot {
+    /// An equal item is stored at this heap position
+    Stored(usize),
+    /// An item with the fingerprint got evicted already and can't be compared anymore
+    Evicted,
+    /// The item is new and can take this slot
+    Free(u32),
+}
+
+/// Unique priority container which only remembers a fingerprint of each item instead of a
+/// copy, so items don't have to implement `Clone`.
+///
+/// Pushing an item whose fingerprint matches a stored item's one compares both items with
+/// `Eq`, distinct items sharing a fingerprint are stored next to each other. Items that were
+/// evicted already can't be compared anymore, so a new item sharing a fingerprint with one
+/// of them gets dropped as a duplicate. The chance of this happening can be lowered by using
+/// `u128` fingerprints or `DedupPolicy::Retained`. Containers with `u128` fingerprints can be
+/// created using `with_hasher`
+pub type FingerprintUniquePrioContainer<T, F = u64, S = RandomState> =
+    FingerprintTopK<T, Min, F, S>;
+
+/// Fingerprint based unique container keeping the best items according to the order policy
+/// `O`. The fingerprint based prio containers are aliases of it
+pub struct FingerprintTopK<T, O = Min, F = u64, S = RandomState> {
+    container: FingerprintHeap<T, F>,
+    /// Highest slot taken by any item for fingerprints shared by distinct items
+    collisions: HashMap<F, u32, BuildHasherDefault<FingerprintHasher>>,
+    hash_builder: S,
+    total_pushed: usize,
+    /// Whether an item got evicted or rejected for its rank since the container was created
+    dropped: bool,
+    capacity: usize,
+    /// Positions of the heap's items sorted from best to worst. Gets reset whenever the heap changes
+    sorted: OnceLock<Vec<usize>>,
+    on_duplicate: DuplicatePolicy<T>,
+    order: PhantomData<fn() -> O>,
+}
+
+impl<T: Ord + Hash, O: Order> FingerprintTopK<T, O> {
+    /// Create a new fingerprint based Unique PrioContainer
+    ///
+    /// # Panics
+    /// Panics if `capacity` is zero
+    #[inline]
+    pub fn new(capacity: usize) -> Self {
+        Self::with_policy(capacity, DedupPolicy::Seen)
+    }
+
+    /// Create a new fingerprint based Unique PrioContainer which dedupes according to `policy`
+    ///
+    /// # Panics
+    /// Panics if `capacity` is zero
+    #[inline]
+    pub fn with_policy(capacity: usize, policy: DedupPolicy) -> Self {
+        let heap = Heap::new(Unique::with_hasher(policy, Default::default()));
+        Self::with_heap(capacity, heap, RandomState::new())
+    }
+
+    /// Create a new fingerprint based Unique PrioContainer with `capacity`. Returns an error
+    /// instead of panicking if `capacity` is zero
+    #[inline]
+    pub fn try_new(capacity: usize) -> Result<Self> {
+        if capacity == 0 {
+            return Err(Error::ZeroCapacity);
+        }
+        Ok(Self::new(capacity))
+    }
+}
+
+impl<T, O, F, S> FingerprintTopK<T, O, F, S>
+where
+    T: Ord + Hash,
+    O: Order,
+    F: Fingerprint,
+    S: BuildHasher,
+{
+    /// Create a new fingerprint based Unique PrioContainer which computes fingerprints using
+    /// `hash_builder`
+    ///
+    /// # Panics
+    /// Panics if `capacity` is zero
+    #[inline]
+    pub fn with_hasher(capacity: usize, hash_builder: S) -> Self {
+        Self::with_policy_and_hasher(capacity, DedupPolicy::Seen, hash_builder)
+    }
+
+    /// Create a new fingerprint based Unique PrioContainer which dedupes according to `policy`
+    /// and computes fingerprints using `hash_builder`
+    ///
+    /// # Panics
+    /// Panics if `capacity` is zero
+    #[inline]
+    pub fn with_policy_and_hasher(capacity: usize, policy: DedupPolicy, hash_builder: S) -> Self {
+        let heap = Heap::new(Unique::with_hasher(policy, Default::default()));
+        Self::with_heap(capacity, heap, hash_builder)
+    }
+
+    fn with_heap(capacity: usize, container: FingerprintHeap<T, F>, hash_builder: S) -> Self {
+        if capacity == 0 {
+            panic!("Capacity can't be zero");
+        }
+
+        Self {
+            container,
+            collisions: HashMap::default(),
+            hash_builder,
+            total_pushed: 0,
+            dropped: false,
+            capacity,
+            sorted: OnceLock::new(),
+            on_duplicate: DuplicatePolicy::KeepBest,
+            order: PhantomData,
+        }
+    }
+
+    /// Inserts a new item. An item equal to a stored one gets resolved according to the
+    /// container's `DuplicatePolicy`
+    #[inline]
+    pub fn insert(&mut self, item: T) -> bool {
+        self.total_pushed += 1;
+        self.insert_item(item)
+    }
+
+    /// Like `insert` but returns an error if allocating memory for the item fails
+    #[inline]
+    pub fn try_insert(&mut self, item: T) -> Result<bool> {
+        let fp = F::of(&item, &self.hash_builder);
+        if let Slot::Free(slot) = self.find_slot(fp, |i| *i == item) {
+            if slot > 0 {
+                self.collisions.try_reserve(1)?;
+            }
+            self.container.index.try_reserve_key()?;
+            if self.container.len() < self.capacity {
+                self.container.try_reserve()?;
+            }
+        }
+        Ok(self.insert(item))
+    }
+
+    /// Merges `other` into the container, keeping the best items of both. Items pushed into
+    /// both containers are only kept once. Fingerprints only get compared within one container,
+    /// so items `other` evicted already are forgotten
+    pub fn merge(&mut self, other: Self) {
+        self.total_pushed += other.total_pushed;
+        self.dropped |= other.dropped;
+
+        for item in other.into_items() {
+            self.insert_item(item);
+        }
+    }
+
+    /// Inserts an already counted item
+    fn insert_item(&mut self, item: T) -> bool {
+        let fp = F::of(&item, &self.hash_builder);
+        match self.find_slot(fp, |i| *i == item) {
+            Slot::Stored(pos) => {
+                self.replace_eq(pos, item);
+                false
+            }
+            Slot::Evicted => false,
+            Slot::Free(slot) => {
+                if slot > 0 {
+                    let last = self.collisions.entry(fp).or_default();
+                    *last = (*last).max(slot);
+                }
+                self.push_heap(Fingerprinted {
+                    key: (fp, slot),
+                    item,
+                })
+            }
+        }
+    }
+
+    /// Looks for the slot of an item with fingerprint `fp` for which `eq` returns `true`
+    fn find_slot<G: FnMut(&T) -> bool>(&self, fp: F, mut eq: G) -> Slot {
+        let slots = self.collisions.get(&fp).map_or(1, |last| last + 1);
+        let mut free = None;
+        let mut evicted = false;
+
+        for slot in 0..slots {
+            let key = (fp, slot);
+            if !self.container.seen(&key) {
+                free.get_or_insert(slot);
+                continue;
+            }
+
+            match self.container.position(&key) {
+                Some(pos) if eq(&self.container.as_slice()[pos].item) => {
+                    return Slot::Stored(pos);
+                }
+                Some(_) => {}
+                None => evicted = true,
+            }
+        }
+
+        if evicted {
+            return Slot::Evicted;
+        }
+        Slot::Free(free.unwrap_or(slots))
+    }
+
+    /// Removes all stored items for which `f` returns `false`. Removed items are forgotten
+    /// and can be inserted again
+    pub fn retain<G: FnMut(&T) -> bool>(&mut self, mut f: G) {
+        let collisions = &self.collisions;
+        let mut freed = Vec::new();
+        let removed = self.container.retain(
+            |i| {
+                let keep = f(&i.item);
+                if !keep && collisions.contains_key(&i.key.0) {
+                    freed.push(i.key.0);
+                }
+                keep
+            },
+            Self::rank,
+        );
+        if removed {
+            self.sorted.take();
+        }
+        for fp in freed {
+            self.prune_collisions(fp);
+        }
+    }
+
+    /// Removes `item` from the container and forgets its fingerprint, so it can be inserted
+    /// again. Returns `true` if it was stored
+    #[inline]
+    pub fn remove(&mut self, item: &T) -> bool {
+        let fp = F::of(item, &self.hash_builder);
+        let Slot::Stored(pos) = self.find_slot(fp, |i| i == item) else {
+            return false;
+        };
+
+        let key = self.container.as_slice()[pos].key;
+        self.container.forget(&key, Self::rank);
+        self.sorted.take();
+        self.prune_collisions(fp);
+        true
+    }
+
+    /// Lowers the highest slot of `fp` to the last one still taken, dropping its entry once
+    /// only the first slot may be taken
+    fn prune_collisions(&mut self, fp: F) {
+        let Some(last) = self.collisions.get_mut(&fp) else {
+            return;
+        };
+        while *last > 0 && !self.container.seen(&(fp, *last)) {
+            *last -= 1;
+        }
+        if *last == 0 {
+            self.collisions.remove(&fp);
+        }
+    }
+
+    /// Changes the capacity of the container. Shrinking evicts the worst items, which are
+    /// treated according to the `DedupPolicy` just like items evicted by `insert`. Returns
+    /// `true` if the container grew after it already dropped items, in which case it may be
+    /// missing items that would fit into the new capacity
+    pub fn set_capacity(&mut self, capacity: usize) -> Result<bool> {
+        if capacity == 0 {
+            return Err(Error::ZeroCapacity);
+        }
+
+        let grown = capacity > self.capacity;
+        self.capacity = capacity;
+
+        while self.container.len() > capacity {
+            self.container.pop(Self::rank);
+            self.sorted.take();
+            self.dropped = true;
+        }
+
+        Ok(grown && self.dropped)
+    }
+
+    /// Pushes `item` onto the heap if it ranks good enough
+    fn push_heap(&mut self, item: Fingerprinted<T, F>) -> bool {
+        if self.container.len() < self.capacity {
+            self.container.push(item, Self::rank);
+            self.sorted.take();
+            return true;
+        }
+
+        // Either the new item or the current worst one gets dropped
+        self.dropped = true;
+
+        // Safety:
+        //
+        // heap.len() >= n without elements is impossible for n>0 which is enforced in `new()`
+        let min_item = unsafe { self.container.peek().unwrap_unchecked() };
+        if Self::rank(min_item, &item).is_le() {
+            self.container.remember(item.key);
+            return false;
+        }
+
+        self.container.replace_top(item, Self::rank);
+        self.sorted.take();
+
+        true
+    }
+
+    #[inline]
+    pub fn contains(&self, item: &T) -> bool {
+        let fp = F::of(item, &self.hash_builder);
+        matches!(self.find_slot(fp, |i| i == item), Slot::Stored(_))
+    }
+
+    /// Returns the policy used to detect duplicates
+    #[inline]
+    pub fn policy(&self) -> DedupPolicy {
+        self.container.policy()
+    }
+
+    /// Sets how a pushed item gets resolved against an equal item that is still stored
+    #[inline]
+    pub fn set_duplicate_policy(&mut self, policy: DuplicatePolicy<T>) {
+        self.on_duplicate = policy;
+    }
+
+    /// Returns the worst item currently stored once the container is full.
+    /// Items that don't rank better than the threshold will be rejected by `insert`
+    #[inline]
+    pub fn threshold(&self) -> Option<&T> {
+        if self.container.len() < self.capacity {
+            return None;
+        }
+        self.container.peek().map(|i| &i.item)
+    }
+
+    /// Returns `true` if `item` ranks good enough to currently be accepted by `insert`.
+    /// This doesn't check whether `item` is a duplicate
+    #[inline]
+    pub fn would_accept(&self, item: &T) -> bool {
+        self.would_accept_by(|worst| item.cmp(worst))
+    }
+
+    /// Returns an iterator over the stored items sorted from best to worst without consuming
+    /// the container. The order is cached until the container changes
+    #[inline]
+    pub fn iter_sorted(&self) -> SortedIter<'_, T> {
+        SortedIter::new(self.sorted_positions(), self)
+    }
+
+    /// Returns the stored items sorted from best to worst without consuming the container
+    #[inline]
+    pub fn to_sorted_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.sorted_refs().cloned().collect()
+    }
+
+    #[inline]
+    pub(crate) fn sorted_refs(&self) -> impl Iterator<Item = &T> {
+        let items = self.container.as_slice();
+        let order = self.sorted_positions();
+        order.iter().map(move |i| &items[*i].item)
+    }
+
+    /// Returns the heap positions of the stored items sorted from best to worst
+    #[inline]
+    fn sorted_positions(&self) -> &[usize] {
+        self.sorted
+            .get_or_init(|| iter::sorted_order(self.container.as_slice(), Self::rank))
+    }
+
+    #[inline]
+    pub(crate) fn would_accept_by<G: FnOnce(&T) -> Ordering>(&self, f: G) -> bool {
+        self.threshold()
+            .is_none_or(|worst| O::rank(f(worst)) == Ordering::Less)
+    }
+
+    /// Inserts the item built by `f` if `bound` would currently be accepted. `bound` has
+    /// to rank at least as good as the built item. This allows skipping the construction
+    /// of items which can't make it into the container anyway
+    #[inline]
+    pub fn insert_with<G: FnOnce() -> T>(&mut self, bound: &T, f: G) -> bool {
+        self.insert_with_by(|worst| bound.cmp(worst), f)
+    }
+
+    /// Like `insert_with` but `bound` compares the bound against the current threshold
+    #[inline]
+    pub(crate) fn insert_with_by<B, G>(&mut self, bound: B, f: G) -> bool
+    where
+        B: FnOnce(&T) -> Ordering,
+        G: FnOnce() -> T,
+    {
+        if !self.would_accept_by(bound) {
+            self.total_pushed += 1;
+            self.dropped = true;
+            return false;
+        }
+
+        self.insert(f())
+    }
+
+    #[inline]
+    pub fn inc_push(&mut self, delta: usize) {
+        self.total_pushed += delta;
+    }
+
+    /// Return a sorted vec of the prio container
+    #[inline]
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        self.into_iter().best_first().collect()
+    }
+
+    /// Resolves `item` against the equal item stored at `pos` according to the container's
+    /// `DuplicatePolicy`
+    fn replace_eq(&mut self, pos: usize, item: T) {
+        let old = &self.container.as_slice()[pos];
+        let key = old.key;
+        match &mut self.on_duplicate {
+            DuplicatePolicy::KeepBest if O::cmp(&item, &old.item).is_lt() => {
+                self.container
+                    .replace_at(pos, Fingerprinted { key, item }, Self::rank);
+            }
+            DuplicatePolicy::KeepBest | DuplicatePolicy::KeepFirst => return,
+            DuplicatePolicy::KeepLast => {
+                self.container
+                    .replace_at(pos, Fingerprinted { key, item }, Self::rank);
+            }
+            DuplicatePolicy::Combine(f) => self.container.update(
+                pos,
+                |old| Fingerprinted {
+                    key,
+                    item: f(old.item, item),
+                },
+                Self::rank,
+            ),
+        }
+        self.sorted.take();
+    }
+
+    /// Removes all items sorted from best to worst. Their fingerprints stay remembered
+    /// depending on the policy
+    pub(crate) fn take_sorted(&mut self) -> Vec<T> {
+        self.sorted.take();
+        let mut items = self.container.take();
+        items.sort_unstable_by(Self::rank);
+        items.into_iter().map(|i| i.item).collect()
+    }
+
+    /// Compares the ranks of two stored items, the better one compares smaller
+    #[inline]
+    fn rank(a: &Fingerprinted<T, F>, b: &Fingerprinted<T, F>) -> Ordering {
+        O::cmp(&a.item, &b.item)
+    }
+}
+
+impl<T, O, F, S> FingerprintTopK<T, O, F, S> {
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.container.len()
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.container.is_empty()
+    }
+
+    #[inline]
+    pub fn total_pushed(&self) -> usize {
+        self.total_pushed
+    }
+
+    /// Returns the stored items in no particular order. Unlike sorting them this takes O(n)
+    #[inline]
+    pub fn into_vec(self) -> Vec<T> {
+        self.into_items().collect()
+    }
+
+    /// Returns an iterator over the stored items in no particular order
+    #[inline]
+    pub fn into_unsorted_iter(self) -> std::vec::IntoIter<T> {
+        self.into_vec().into_iter()
+    }
+
+    /// Returns the stored items in heap order
+    #[inline]
+    pub(crate) fn into_items(self) -> impl Iterator<Item = T> {
+        self.container.into_parts().0.into_iter().map(|i| i.item)
+    }
+}
+
+impl<T, O, F, S> ItemAt<T> for FingerprintTopK<T, O, F, S> {
+    #[inline]
+    fn item_at(&self, pos: usize) -> &T {
+        &self.container.as_slice()[pos].item
+    }
+}
+
+impl<T, O, F, S> Extend<T> for FingerprintTopK<T, O, F, S>
+where
+    T: Ord + Hash,
+    O: Order,
+    F: Fingerprint,
+    S: BuildHasher,
+{
+    #[inline]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for i in iter {
+            self.insert(i);
+        }
+    }
+}
+
+impl<T: Ord, O: Order, F, S> IntoIterator for FingerprintTopK<T, O, F, S> {
+    type Item = T;
+    type IntoIter = IntoIter<T, O>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter::new(self.into_items().collect())
+    }
+}
+
+impl<T, O, F, S> TopKContainer<T> for FingerprintTopK<T, O, F, S>
+where
+    T: Ord + Hash,
+    O: Order,
+    F: Fingerprint,
+    S: BuildHasher,
+{
+    #[inline]
+    fn insert(&mut self, item: T) -> bool {
+        self.insert(item)
+    }
+
+    #[inline]
+    fn threshold(&self) -> Option<&T> {
+        self.threshold()
+    }
+
+    #[inline]
+    fn would_accept(&self, item: &T) -> bool {
+        self.would_accept(item)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.capacity()
+    }
+
+    #[inline]
+    fn total_pushed(&self) -> usize {
+        self.total_pushed()
+    }
+
+    #[inline]
+    fn iter_sorted(&self) -> SortedIter<'_, T> {
+        self.iter_sorted()
+    }
+
+    #[inline]
+    fn drain_sorted(&mut self) -> Vec<T> {
+        self.take_sorted()
+    }
+}
@@ -0,0 +1,8 @@
+use super::fingerprint::FingerprintTopK;
+use crate::policy::Max;
+use std::collections::hash_map::RandomState;
+
+/// Like [`FingerprintUniquePrioContainer`](super::fingerprint::FingerprintUniquePrioContainer)
+/// but keeps the biggest items
+pub type FingerprintUniquePrioContainerMax<T, F = u64, S = RandomState> =
+    FingerprintTopK<T, Max, F, S>;
@@ -1,137 +1,74 @@
+pub mod fingerprint;
+pub mod fingerprint_max;
 pub mod max;
 pub mod stable;
 pub mod stable_max;
 
-use std::{
-    collections::{BinaryHeap, HashSet},
-    hash::Hash,
+use crate::{
+    policy::{Min, Unique, Unstable},
+    TopK,
 };
-
-use crate::iter::SortedHeapIter;
-
-/// Priority container storing max `capacity` amount of items. Can be used to find
-/// `n` smallest items within an iterator or a set of items that implement `Ord`.
-/// This PrioContainer is stable
-pub struct UniquePrioContainer<T> {
-    container: BinaryHeap<T>,
-    hash: HashSet<T>,
-    total_pushed: usize,
-    capacity: usize,
+use std::collections::hash_map::RandomState;
+
+/// Decides which items a unique container remembers to detect duplicates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DedupPolicy {
+    /// Remember every distinct item ever pushed. An evicted item which gets pushed again is
+    /// rejected as a duplicate, even if it ranks better now. Memory grows with the amount of
+    /// distinct items pushed
+    #[default]
+    Seen,
+    /// Only dedupe among the currently stored items. Evicted and rejected items are forgotten
+    /// and get accepted again if they rank good enough later on. Memory stays bounded by the
+    /// capacity
+    Retained,
 }
 
-impl<T: Ord + Clone + Hash> UniquePrioContainer<T> {
-    /// Create a new Unique PrioContainer
-    #[inline]
-    pub fn new(capacity: usize) -> Self {
-        let container = BinaryHeap::new();
-        let hash = HashSet::new();
-
-        Self {
-            container,
-            hash,
-            total_pushed: 0,
-            capacity,
-        }
-    }
-
-    /// Create a new Unique PrioContainer
-    #[inline]
-    pub fn new_allocated(capacity: usize) -> Self {
-        let container = BinaryHeap::with_capacity(capacity);
-        let hash = HashSet::with_capacity(capacity);
-
-        Self {
-            container,
-            hash,
-            total_pushed: 0,
-            capacity,
-        }
-    }
-
-    pub fn insert(&mut self, item: T) -> bool {
-        if self.hash.contains(&item) {
-            self.replace_eq(item);
-            return false;
-        }
-
-        self.hash.insert(item.clone());
-
-        if self.container.len() < self.capacity {
-            self.container.push(item);
-            self.total_pushed += 1;
-            return true;
-        }
-
-        // Safety:
-        //
-        // heap.len() >= n without elements is impossible for n>0 which is enforced in `PrioContainer::new()`
-        let min_item = unsafe { self.container.peek().unwrap_unchecked() };
-        if *min_item <= item {
-            self.total_pushed += 1;
-            return false;
-        }
-
-        *unsafe { self.container.peek_mut().unwrap_unchecked() } = item;
-        self.total_pushed += 1;
-
-        true
-    }
-
-    #[inline]
-    pub fn contains(&self, item: &T) -> bool {
-        self.container.iter().any(|i| *i == *item)
-    }
-
-    /// Replaces an already pushed item with `item` if their hashes are equal
-    /// and `item`'s relevance is bigger
-    fn replace_eq(&mut self, item: T) {
-        let need_replace = self.container.iter().any(|i| *i == item && item < *i);
-
-        if need_replace {
-            let add: Vec<_> = self.container.drain().filter(|i| *i != item).collect();
-            self.container.extend(add);
-            self.container.push(item);
-        }
-    }
+/// Decides what happens when an item gets pushed while an equal item is stored. Items that
+/// were evicted already aren't affected
+pub enum DuplicatePolicy<T> {
+    /// Keep the better ranked item
+    KeepBest,
+    /// Keep the stored item
+    KeepFirst,
+    /// Replace the stored item with the new one, even if it ranks worse
+    KeepLast,
+    /// Replace the stored item with the one returned by the closure, which gets called with
+    /// the stored and the new item. The returned item has to be equal to both of them
+    Combine(Box<dyn FnMut(T, T) -> T + Send + Sync>),
 }
 
-impl<T> UniquePrioContainer<T> {
-    #[inline]
-    pub fn len(&self) -> usize {
-        self.container.len()
-    }
-
+impl<T> DuplicatePolicy<T> {
+    /// Creates a policy combining duplicates using `f`
     #[inline]
-    pub fn capacity(&self) -> usize {
-        self.container.capacity()
-    }
-
-    #[inline]
-    pub fn is_empty(&self) -> bool {
-        self.container.is_empty()
+    pub fn combine<F>(f: F) -> Self
+    where
+        F: FnMut(T, T) -> T + Send + Sync + 'static,
+    {
+        Self::Combine(Box::new(f))
     }
+}
 
+impl<T> Default for DuplicatePolicy<T> {
     #[inline]
-    pub fn total_pushed(&self) -> usize {
-        self.total_pushed
+    fn default() -> Self {
+        Self::KeepBest
     }
 }
 
-impl<T: Ord + Clone + Hash> Extend<T> for UniquePrioContainer<T> {
-    #[inline]
-    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
-        for i in iter.into_iter() {
-            self.insert(i);
+impl<T> std::fmt::Debug for DuplicatePolicy<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::KeepBest => write!(f, "KeepBest"),
+            Self::KeepFirst => write!(f, "KeepFirst"),
+            Self::KeepLast => write!(f, "KeepLast"),
+            Self::Combine(_) => write!(f, "Combine"),
         }
     }
 }
 
-impl<T: Ord> IntoIterator for UniquePrioContainer<T> {
-    type Item = T;
-    type IntoIter = SortedHeapIter<T>;
-
-    #[inline]
-    fn into_iter(self) -> Self::IntoIter {
-        SortedHeapIter::new(self.container)
-    }
-}
+/// Priority container storing max `capacity` amount of items. Can be used to find
+/// `n` smallest items within an iterator or a set of items that implement `Ord`.
+/// This PrioContainer is stable. Items are deduplicated by their key `K`, which is the item
+/// itself unless the container got created by `unique_by`
+pub type UniquePrioContainer<T, S = RandomState, K = T> = TopK<T, Min, Unstable, Unique<K, S>>;
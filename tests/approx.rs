@@ -0,0 +1,63 @@
+use priority_container::{
+    ApproxUnique, ApproxUniquePrioContainer, ApproxUniquePrioContainerMax, Error,
+    StablePrioContainerMax,
+};
+
+#[test]
+fn test_approx_unique() {
+    let mut heap = ApproxUniquePrioContainerMax::new(3, 100, 0.01);
+    heap.extend([1, 5, 2, 5, 4, 1, 3, 4]);
+    assert_eq!(heap.total_pushed(), 8);
+    assert!(heap.seen(&2));
+    assert!(!heap.insert(5));
+    assert_eq!(heap.total_pushed(), 9);
+    assert_eq!(heap.to_sorted_vec(), vec![5, 4, 3]);
+
+    let mut heap = ApproxUniquePrioContainer::new(2, 100, 0.01);
+    assert_eq!(heap.try_insert(3), Ok(true));
+    assert_eq!(heap.try_insert(3), Ok(false));
+    assert_eq!(heap.total_pushed(), 2);
+    heap.extend([2, 1, 2]);
+    assert_eq!(heap.iter_sorted().copied().collect::<Vec<_>>(), vec![1, 2]);
+}
+
+#[test]
+fn test_approx_stable() {
+    let mut heap = ApproxUnique::<StablePrioContainerMax<(u32, &str)>>::new(2, 10, 0.01);
+    heap.extend([(1, "a"), (2, "b"), (1, "a"), (2, "c")]);
+    assert_eq!(heap.total_pushed(), 4);
+    assert_eq!(heap.to_sorted_vec(), vec![(2, "c"), (2, "b")]);
+}
+
+#[test]
+fn test_estimated_error() {
+    let mut heap = ApproxUniquePrioContainerMax::new(10, 1000, 0.01);
+    assert_eq!(heap.estimated_error(), 0.0);
+
+    heap.extend(0..1000u32);
+    let error = heap.estimated_error();
+    assert!(error > 0.0 && error < 0.02, "{error}");
+    // Wrongly rejected items are counted just like stored ones
+    assert_eq!(heap.total_pushed(), 1000);
+
+    heap.extend(1000..10_000u32);
+    assert!(heap.estimated_error() > 0.5);
+}
+
+#[test]
+fn test_invalid_rate() {
+    for rate in [0.0, 1.0, -0.5, f64::NAN] {
+        let heap = ApproxUniquePrioContainer::<u32>::try_new(3, 100, rate);
+        assert!(matches!(heap, Err(Error::InvalidRate)));
+    }
+    assert!(matches!(
+        ApproxUniquePrioContainer::<u32>::try_new(0, 100, 0.1),
+        Err(Error::ZeroCapacity)
+    ));
+}
+
+#[test]
+#[should_panic]
+fn test_invalid_rate_panics() {
+    ApproxUniquePrioContainerMax::<u32>::new(3, 100, 1.5);
+}
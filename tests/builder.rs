@@ -0,0 +1,133 @@
+use priority_container::{
+    builder,
+    builder::{Build, Max, Stable, Unique, Unstable},
+    Container, DedupPolicy, PrioContainer, StableUniquePrioContainerMax, TieBreak, TopK,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Hit {
+    score: u32,
+    id: &'static str,
+}
+
+impl Hit {
+    fn new(score: u32, id: &'static str) -> Self {
+        Self { score, id }
+    }
+}
+
+#[test]
+fn test_builder() {
+    let mut heap: PrioContainer<u32> = builder().capacity(2).build();
+    heap.extend([3, 1, 4, 1, 5]);
+    assert_eq!(heap.into_sorted_vec(), vec![1, 1]);
+
+    let mut heap = builder::<u32>().capacity(2).max().stable().build();
+    heap.extend([3, 1, 4, 1, 5]);
+    assert_eq!(heap.into_sorted_vec(), vec![5, 4]);
+
+    let mut heap = builder::<u32>().capacity(3).unique().build();
+    heap.extend([3, 1, 4, 1, 5]);
+    assert_eq!(heap.into_sorted_vec(), vec![1, 3, 4]);
+
+    let heap = builder::<u32>().max().unique().stable();
+    let heap: StableUniquePrioContainerMax<u32> = heap.capacity(1).build();
+    assert_eq!(heap.capacity(), 1);
+
+    assert!(builder::<u32>().max().unique().try_build().is_err());
+}
+
+#[test]
+fn test_builder_policies() {
+    let mut heap = builder()
+        .capacity(2)
+        .max()
+        .tie_break(TieBreak::Fifo)
+        .dedup_policy(DedupPolicy::Retained)
+        .build();
+    heap.extend([Hit::new(1, "a"), Hit::new(2, "b"), Hit::new(2, "b")]);
+    assert_eq!(heap.policy(), DedupPolicy::Retained);
+    assert_eq!(heap.len(), 2);
+
+    // The generic alias resolves to the container picked by the builder
+    let heap: Container<u32, Max, Stable<u32>> = builder().capacity(2).max().stable().build();
+    assert_eq!(heap.capacity(), 2);
+}
+
+#[test]
+fn test_builder_unique_by() {
+    let mut heap = builder()
+        .capacity(2)
+        .max()
+        .stable()
+        .unique_by(|h: &Hit| h.id)
+        .build();
+    heap.extend([
+        Hit::new(1, "a"),
+        Hit::new(5, "b"),
+        Hit::new(3, "a"),
+        Hit::new(2, "c"),
+        Hit::new(4, "b"),
+    ]);
+    let ids = heap.iter_sorted().map(|h| h.id).collect::<Vec<_>>();
+    assert_eq!(ids, vec!["b", "a"]);
+
+    let mut heap = builder().capacity(2).unique_by(|h: &Hit| h.id).build();
+    heap.extend([Hit::new(1, "a"), Hit::new(0, "a"), Hit::new(2, "b")]);
+    assert_eq!(
+        heap.to_sorted_vec(),
+        vec![Hit::new(0, "a"), Hit::new(2, "b")]
+    );
+}
+
+#[test]
+fn test_builder_unique_by_policy() {
+    let hits = [Hit::new(2, "a"), Hit::new(1, "b"), Hit::new(0, "a")];
+
+    // IDs of evicted items are remembered by default
+    let mut heap = builder().capacity(1).unique_by(|h: &Hit| h.id).build();
+    heap.extend(hits.clone());
+    assert_eq!(heap.to_sorted_vec(), vec![Hit::new(1, "b")]);
+
+    let mut heap = builder()
+        .capacity(1)
+        .stable()
+        .dedup_policy(DedupPolicy::Retained)
+        .unique_by(|h: &Hit| h.id)
+        .build();
+    heap.extend(hits);
+    assert_eq!(heap.inner().policy(), DedupPolicy::Retained);
+    assert_eq!(heap.to_sorted_vec(), vec![Hit::new(0, "a")]);
+}
+
+#[test]
+fn test_builder_keeps_dedup_policy() {
+    let mut heap: TopK<u32, Max, Unstable, Unique<u32>> = TopK::builder()
+        .capacity(1)
+        .dedup_policy(DedupPolicy::Retained)
+        .unique()
+        .max()
+        .build();
+    heap.extend([1, 3, 1]);
+    assert_eq!(heap.policy(), DedupPolicy::Retained);
+    assert_eq!(heap.into_sorted_vec(), vec![3]);
+
+    let mut heap = TopK::builder()
+        .capacity(1)
+        .dedup_policy(DedupPolicy::Retained)
+        .duplicates()
+        .unique()
+        .build();
+    heap.extend([Hit::new(2, "a"), Hit::new(1, "b"), Hit::new(2, "a")]);
+    assert_eq!(heap.policy(), DedupPolicy::Retained);
+    assert_eq!(heap.into_sorted_vec(), vec![Hit::new(1, "b")]);
+
+    let mut heap = TopK::builder()
+        .capacity(1)
+        .dedup_policy(DedupPolicy::Retained)
+        .unique_by(|h: &Hit| h.id)
+        .build();
+    heap.extend([Hit::new(2, "a"), Hit::new(1, "b"), Hit::new(0, "a")]);
+    assert_eq!(heap.inner().policy(), DedupPolicy::Retained);
+    assert_eq!(heap.to_sorted_vec(), vec![Hit::new(0, "a")]);
+}
@@ -0,0 +1,217 @@
+use priority_container::{
+    PrioContainer, PrioContainerMax, StablePrioContainerMax, StableUniquePrioContainer,
+    UniquePrioContainer, UniquePrioContainerMax,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Doc {
+    id: &'static str,
+    score: u32,
+}
+
+impl Doc {
+    fn new(id: &'static str, score: u32) -> Self {
+        Self { id, score }
+    }
+}
+
+fn docs() -> Vec<Doc> {
+    vec![
+        Doc::new("a", 5),
+        Doc::new("b", 1),
+        Doc::new("c", 9),
+        Doc::new("d", 3),
+        Doc::new("e", 7),
+    ]
+}
+
+#[test]
+fn test_by_key() {
+    let mut container = PrioContainer::by_key(2, |d: &Doc| d.score);
+    container.extend(docs());
+    assert_eq!(container.len(), 2);
+    assert_eq!(container.total_pushed(), 5);
+
+    let out = container.into_iter().map(|d| d.id).collect::<Vec<_>>();
+    assert_eq!(out, vec!["d", "b"]);
+}
+
+#[test]
+fn test_by_key_max() {
+    let mut container = PrioContainerMax::by_key(3, |d: &Doc| d.score);
+    container.extend(docs());
+
+    let out = container.into_iter().map(|d| d.id).collect::<Vec<_>>();
+    assert_eq!(out, vec!["a", "e", "c"]);
+}
+
+#[test]
+fn test_by_cmp() {
+    let mut container = PrioContainer::by(2, |a: &Doc, b: &Doc| b.id.cmp(a.id));
+    container.extend(docs());
+
+    let out = container.into_iter().map(|d| d.id).collect::<Vec<_>>();
+    assert_eq!(out, vec!["d", "e"]);
+}
+
+#[test]
+fn test_by_cmp_capturing() {
+    // The comparator owns its ranking and isn't `Copy`
+    let rank = ["e", "a", "c", "b", "d"].to_vec();
+    let pos = move |d: &Doc| rank.iter().position(|i| *i == d.id);
+    let mut container = PrioContainerMax::by(2, move |a: &Doc, b: &Doc| pos(b).cmp(&pos(a)));
+    container.extend(docs());
+
+    let out = container.into_iter().map(|d| d.id).collect::<Vec<_>>();
+    assert_eq!(out, vec!["a", "e"]);
+}
+
+#[test]
+fn test_by_key_stable() {
+    let mut container = StablePrioContainerMax::by_key(5, |d: &Doc| d.score / 10);
+    container.extend(docs());
+
+    // All keys are equal so items are returned in inserted order
+    let out = container.into_iter().map(|d| d.id).collect::<Vec<_>>();
+    assert_eq!(out, vec!["a", "b", "c", "d", "e"]);
+}
+
+#[test]
+fn test_by_key_unique() {
+    let mut container = UniquePrioContainerMax::by_key(10, |(_, score): &(&str, u32)| *score);
+    container.insert(("a", 1));
+    container.insert(("b", 2));
+    container.insert(("a", 1));
+    container.insert(("b", 2));
+    assert_eq!(container.len(), 2);
+
+    let mut container = StableUniquePrioContainer::by(10, |a: &Doc, b: &Doc| a.score.cmp(&b.score));
+    container.extend(docs());
+    container.extend(docs());
+    assert_eq!(container.len(), 5);
+}
+
+#[test]
+fn test_by_key_threshold() {
+    let mut container = PrioContainerMax::by_key(2, |d: &Doc| d.score);
+    assert_eq!(container.threshold_key(), None);
+    container.extend(docs());
+
+    assert_eq!(container.threshold().map(|d| d.id), Some("e"));
+    assert_eq!(container.threshold_key(), Some(&7));
+    assert!(container.would_accept_key(&8));
+    assert!(!container.would_accept(&Doc::new("f", 7)));
+}
+
+#[test]
+fn test_by_key_insert_with() {
+    let mut container = PrioContainerMax::by_key(2, |d: &Doc| d.score);
+    container.extend(docs());
+
+    assert!(!container.insert_with(&6, || unreachable!()));
+    assert!(container.insert_with(&10, || Doc::new("f", 8)));
+    assert_eq!(container.threshold_key(), Some(&8));
+    assert_eq!(container.total_pushed(), 7);
+}
+
+#[test]
+fn test_by_remove() {
+    let mut container = PrioContainerMax::by_key(3, |d: &Doc| d.score);
+    container.extend(docs());
+    // Only the stored item with an equal key and equal fields gets removed
+    assert!(!container.remove(&Doc::new("x", 9)));
+    assert!(container.remove(&Doc::new("c", 9)));
+    assert!(!container.remove(&Doc::new("c", 9)));
+    assert_eq!(container.len(), 2);
+
+    let mut container = UniquePrioContainer::by(3, |a: &Doc, b: &Doc| a.score.cmp(&b.score));
+    container.extend(docs());
+    assert!(container.remove(&Doc::new("b", 1)));
+    // Removed items are forgotten and can be inserted again
+    assert!(container.insert(Doc::new("b", 1)));
+    let out = container.into_iter().map(|d| d.id).collect::<Vec<_>>();
+    assert_eq!(out, vec!["a", "d", "b"]);
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Hit {
+    score: u32,
+    id: &'static str,
+}
+
+#[test]
+fn test_unique_by() {
+    let hit = |id, score| Hit { score, id };
+
+    let mut container = UniquePrioContainerMax::unique_by(2, |h: &Hit| h.id);
+    container.extend([
+        hit("a", 1),
+        hit("b", 5),
+        hit("a", 7),
+        hit("c", 3),
+        hit("b", 2),
+    ]);
+    assert_eq!(container.len(), 2);
+
+    // "a" got replaced by its better hit while "b" kept its best one
+    let out = container.to_sorted_vec();
+    assert_eq!(out, vec![hit("a", 7), hit("b", 5)]);
+
+    let mut container = StableUniquePrioContainer::unique_by(3, |h: &Hit| h.id);
+    container.extend([hit("a", 1), hit("b", 1), hit("a", 0), hit("c", 1)]);
+    container.retain(|h| h.id != "b");
+    let out = container
+        .into_iter()
+        .map(|h| (h.id, h.score))
+        .collect::<Vec<_>>();
+    assert_eq!(out, vec![("c", 1), ("a", 0)]);
+}
+
+#[test]
+fn test_unique_by_remove() {
+    let hit = |id, score| Hit { score, id };
+
+    let mut container = UniquePrioContainerMax::unique_by(3, |h: &Hit| h.id);
+    container.extend([hit("a", 1), hit("b", 5), hit("c", 3)]);
+    // Items are found by their ID only
+    assert!(container.remove(&hit("b", 0)));
+    assert!(!container.remove(&hit("b", 5)));
+    assert!(container.insert(hit("b", 2)));
+    assert_eq!(
+        container.to_sorted_vec(),
+        vec![hit("c", 3), hit("b", 2), hit("a", 1)]
+    );
+}
+
+/// Deliberately not `Clone`, `unique_by` only needs to clone the IDs
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Page {
+    rank: u32,
+    url: String,
+}
+
+#[test]
+fn test_unique_by_non_clone() {
+    let page = |url: &str, rank| Page {
+        rank,
+        url: url.to_string(),
+    };
+
+    let mut container = UniquePrioContainer::unique_by(2, |p: &Page| p.url.len());
+    container.extend([page("ab", 3), page("cd", 1), page("xyz", 2), page("c", 4)]);
+    assert_eq!(container.total_pushed(), 4);
+
+    let out = container.into_iter().collect::<Vec<_>>();
+    assert_eq!(out, vec![page("xyz", 2), page("cd", 1)]);
+}
+
+#[test]
+fn test_unique_by_with_hasher() {
+    use std::hash::{BuildHasherDefault, DefaultHasher};
+
+    let hit = |id, score| Hit { score, id };
+    let hasher = BuildHasherDefault::<DefaultHasher>::default();
+    let mut container = UniquePrioContainerMax::unique_by_with_hasher(2, |h: &Hit| h.id, hasher);
+    container.extend([hit("a", 1), hit("b", 5), hit("a", 7), hit("b", 2)]);
+    assert_eq!(container.to_sorted_vec(), vec![hit("a", 7), hit("b", 5)]);
+}
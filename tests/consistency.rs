@@ -0,0 +1,78 @@
+//! Asserts that all container variants share the same surface and behave alike
+
+use priority_container::{
+    PrioContainer, PrioContainerMax, StablePrioContainer, StablePrioContainerMax,
+    StableUniquePrioContainer, StableUniquePrioContainerMax, UniquePrioContainer,
+    UniquePrioContainerMax,
+};
+
+const INPUT: [u32; 7] = [5, 1, 4, 2, 8, 7, 3];
+const BEST_MIN: [u32; 3] = [1, 2, 3];
+const BEST_MAX: [u32; 3] = [8, 7, 5];
+
+macro_rules! check_container {
+    ($name:ident, $container:ident, $best:expr, $rejected:expr) => {
+        #[test]
+        fn $name() {
+            let best = $best.to_vec();
+            let mut worst = best.clone();
+            worst.reverse();
+
+            assert!($container::<u32>::try_new(0).is_err());
+
+            let mut heap = $container::new_allocated(3, 10);
+            heap.extend(INPUT);
+            assert_eq!(heap.capacity(), 3);
+            assert_eq!(heap.len(), 3);
+            assert!(!heap.is_empty());
+            assert_eq!(heap.total_pushed(), INPUT.len());
+            assert_eq!(heap.threshold(), best.last());
+            assert!(heap.contains(&best[0]));
+            assert!(!heap.contains(&$rejected));
+            assert_eq!(heap.iter_sorted().copied().collect::<Vec<_>>(), best);
+            assert_eq!(heap.to_sorted_vec(), best);
+
+            // Duplicates count as pushes
+            heap.insert(best[0]);
+            heap.inc_push(2);
+            assert_eq!(heap.total_pushed(), INPUT.len() + 3);
+
+            let mut heap = $container::new(3);
+            heap.extend(INPUT);
+            let out: Vec<u32> = heap.into_iter().collect();
+            assert_eq!(out, worst);
+
+            let mut heap = $container::new(3);
+            heap.extend(INPUT);
+            assert_eq!(heap.into_iter().best_first().collect::<Vec<_>>(), best);
+
+            let mut heap = $container::new(3);
+            heap.extend(INPUT);
+            assert_eq!(heap.into_sorted_vec(), best);
+
+            let mut sorted = best.clone();
+            sorted.sort_unstable();
+
+            let mut heap = $container::new(3);
+            heap.extend(INPUT);
+            let mut out = heap.into_vec();
+            out.sort_unstable();
+            assert_eq!(out, sorted);
+
+            let mut heap = $container::new(3);
+            heap.extend(INPUT);
+            let mut out = heap.into_unsorted_iter().collect::<Vec<_>>();
+            out.sort_unstable();
+            assert_eq!(out, sorted);
+        }
+    };
+}
+
+check_container! { test_plain, PrioContainer, BEST_MIN, 8 }
+check_container! { test_plain_max, PrioContainerMax, BEST_MAX, 1 }
+check_container! { test_stable, StablePrioContainer, BEST_MIN, 8 }
+check_container! { test_stable_max, StablePrioContainerMax, BEST_MAX, 1 }
+check_container! { test_unique, UniquePrioContainer, BEST_MIN, 8 }
+check_container! { test_unique_max, UniquePrioContainerMax, BEST_MAX, 1 }
+check_container! { test_stable_unique, StableUniquePrioContainer, BEST_MIN, 8 }
+check_container! { test_stable_unique_max, StableUniquePrioContainerMax, BEST_MAX, 1 }
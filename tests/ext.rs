@@ -0,0 +1,101 @@
+use priority_container::TopKExt;
+use std::cmp::Ordering;
+
+/// Item ranked by its score only, so items with different ids tie
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Hit {
+    id: u32,
+    score: u32,
+}
+
+impl Ord for Hit {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score.cmp(&other.score)
+    }
+}
+
+impl PartialOrd for Hit {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[test]
+fn test_top_k() {
+    let items = [3, 1, 4, 1, 5, 9, 2, 6];
+    assert_eq!(items.into_iter().top_k(3), vec![9, 6, 5]);
+    assert_eq!(items.into_iter().bottom_k(3), vec![1, 1, 2]);
+    assert_eq!(items.into_iter().top_k(20).len(), items.len());
+    assert!(items.into_iter().top_k(0).is_empty());
+
+    assert_eq!(items.into_iter().top_k_unique(3), vec![9, 6, 5]);
+    assert_eq!(items.into_iter().bottom_k_unique(3), vec![1, 2, 3]);
+
+    let words = ["pear", "fig", "banana", "kiwi"];
+    assert_eq!(
+        words.iter().top_k_by_key(2, |w| w.len()),
+        vec![&"banana", &"pear"]
+    );
+    assert_eq!(words.iter().bottom_k_by_key(1, |w| w.len()), vec![&"fig"]);
+}
+
+#[test]
+fn test_top_k_stable() {
+    let hits = (1..=5).map(|id| Hit { id, score: id % 2 });
+    let ids = |hits: Vec<Hit>| hits.into_iter().map(|h| h.id).collect::<Vec<_>>();
+    assert_eq!(ids(hits.clone().top_k_stable(3)), vec![1, 3, 5]);
+    assert_eq!(ids(hits.bottom_k_stable(2)), vec![2, 4]);
+}
+
+#[test]
+fn test_try_top_k() {
+    let items: Vec<Result<u32, &str>> = vec![Ok(3), Ok(1), Ok(4)];
+    assert_eq!(items.into_iter().try_top_k(2), Ok(vec![4, 3]));
+
+    let mut items = vec![Ok(3), Err("first"), Ok(4), Err("second")].into_iter();
+    assert_eq!(items.by_ref().try_bottom_k(2), Err("first"));
+    // Stops at the first error
+    assert_eq!(items.next(), Some(Ok(4)));
+
+    // Nothing gets consumed without room for items
+    let mut items = vec![Ok(3), Err("first")].into_iter();
+    assert_eq!(items.by_ref().try_top_k(0), Ok(vec![]));
+    assert_eq!(items.len(), 2);
+    assert!(items.by_ref().top_k_stable(0).is_empty());
+    assert_eq!(items.len(), 2);
+}
+
+#[test]
+fn test_try_variants() {
+    let hits = (1..=5).map(|id| Ok::<_, &str>(Hit { id, score: id % 2 }));
+    let ids =
+        |hits: Result<Vec<Hit>, &'static str>| hits.map(|h| h.into_iter().map(|h| h.id).collect());
+    assert_eq!(ids(hits.clone().try_top_k_stable(3)), Ok(vec![1, 3, 5]));
+    assert_eq!(ids(hits.try_bottom_k_stable(2)), Ok(vec![2, 4]));
+
+    let words = [Ok("pear"), Ok("fig"), Err("eof"), Ok("banana")];
+    assert_eq!(
+        words.into_iter().try_top_k_by_key(1, |w| w.len()),
+        Err("eof")
+    );
+    let words = words.into_iter().take(2);
+    assert_eq!(words.try_bottom_k_by_key(1, |w| w.len()), Ok(vec!["fig"]));
+
+    let items = [Ok(3), Ok(1), Ok(3), Ok(2)];
+    assert_eq!(
+        items.into_iter().try_top_k_unique::<_, ()>(2),
+        Ok(vec![3, 2])
+    );
+    assert_eq!(
+        items.into_iter().try_bottom_k_unique::<_, ()>(2),
+        Ok(vec![1, 2])
+    );
+}
+
+#[test]
+fn test_top_k_stable_borrowed() {
+    // Items borrowing local data don't have to be `'static`
+    let text = String::from("b a c a");
+    let words = text.split(' ').collect::<Vec<_>>();
+    assert_eq!(words.iter().copied().top_k_stable(2), vec!["c", "b"]);
+}
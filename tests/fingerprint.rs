@@ -0,0 +1,195 @@
+use priority_container::{
+    DedupPolicy, DuplicatePolicy, FingerprintUniquePrioContainer,
+    FingerprintUniquePrioContainerMax, StableUniquePrioContainerMax, UniquePrioContainerMax,
+};
+use std::hash::{BuildHasherDefault, Hash, Hasher};
+
+/// Item which can't be cloned
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Doc {
+    id: u32,
+    body: Vec<u8>,
+}
+
+impl Doc {
+    fn new(id: u32) -> Self {
+        Self {
+            id,
+            body: vec![id as u8; 16],
+        }
+    }
+}
+
+#[test]
+fn test_fingerprint() {
+    let mut heap = FingerprintUniquePrioContainerMax::new(3);
+    heap.extend([1, 5, 2, 5, 4, 1, 3].map(Doc::new));
+    assert_eq!(heap.len(), 3);
+    assert!(heap.contains(&Doc::new(4)));
+    assert!(!heap.contains(&Doc::new(1)));
+
+    assert!(heap.remove(&Doc::new(4)));
+    heap.insert(Doc::new(4));
+
+    let out = heap.into_iter().map(|d| d.id).collect::<Vec<_>>();
+    assert_eq!(out, vec![3, 4, 5]);
+}
+
+#[test]
+fn test_fingerprint_u128() {
+    let hasher = std::collections::hash_map::RandomState::new();
+    let mut heap = FingerprintUniquePrioContainer::<Doc, u128, _>::with_hasher(2, hasher);
+    heap.extend([3, 1, 3, 2, 1].map(Doc::new));
+    let out = heap.iter_sorted().map(|d| d.id).collect::<Vec<_>>();
+    assert_eq!(out, vec![1, 2]);
+}
+
+/// Hasher giving every item the same fingerprint
+#[derive(Default)]
+struct ConstHasher;
+
+impl Hasher for ConstHasher {
+    fn finish(&self) -> u64 {
+        0
+    }
+
+    fn write(&mut self, _: &[u8]) {}
+}
+
+#[test]
+fn test_fingerprint_collision() {
+    let hasher = BuildHasherDefault::<ConstHasher>::default();
+    let mut heap = FingerprintUniquePrioContainer::<Doc, u64, _>::with_hasher(2, hasher);
+
+    // Distinct items sharing a fingerprint are stored next to each other
+    assert!(heap.insert(Doc::new(2)));
+    assert!(heap.insert(Doc::new(1)));
+    assert!(!heap.insert(Doc::new(2)));
+    assert!(heap.contains(&Doc::new(2)));
+    assert!(heap.contains(&Doc::new(1)));
+    assert_eq!(heap.len(), 2);
+
+    assert!(heap.remove(&Doc::new(2)));
+    assert!(!heap.remove(&Doc::new(2)));
+    assert!(heap.contains(&Doc::new(1)));
+    assert!(heap.insert(Doc::new(0)));
+    assert_eq!(heap.into_sorted_vec(), vec![Doc::new(0), Doc::new(1)]);
+
+    // Evicted fingerprints are forgotten, so they can never cause false duplicates
+    let hasher = BuildHasherDefault::<ConstHasher>::default();
+    let mut heap = FingerprintUniquePrioContainer::<u32, u64, _>::with_policy_and_hasher(
+        1,
+        DedupPolicy::Retained,
+        hasher,
+    );
+    heap.extend([2, 1, 2, 0]);
+    assert_eq!(heap.into_iter().collect::<Vec<_>>(), vec![0]);
+}
+
+#[test]
+fn test_fingerprint_collision_removed() {
+    let hasher = BuildHasherDefault::<ConstHasher>::default();
+    let mut heap = FingerprintUniquePrioContainer::<Doc, u64, _>::with_policy_and_hasher(
+        3,
+        DedupPolicy::Retained,
+        hasher,
+    );
+    heap.extend([1, 2, 3].map(Doc::new));
+    assert!(heap.remove(&Doc::new(3)));
+    heap.retain(|d| d.id == 1);
+    assert!(heap.remove(&Doc::new(1)));
+    assert!(heap.is_empty());
+
+    // The freed slots get taken again by new colliding items
+    heap.extend([6, 5, 4].map(Doc::new));
+    assert_eq!(heap.len(), 3);
+    assert!(heap.contains(&Doc::new(6)));
+    heap.retain(|d| d.id != 5);
+    assert!(heap.insert(Doc::new(2)));
+    assert!(!heap.insert(Doc::new(2)));
+    let out = heap.into_sorted_vec().into_iter().map(|d| d.id);
+    assert_eq!(out.collect::<Vec<_>>(), vec![2, 4, 6]);
+}
+
+#[test]
+fn test_fingerprint_merge() {
+    let mut a = FingerprintUniquePrioContainerMax::new(3);
+    a.extend([1, 4, 2].map(Doc::new));
+    let mut b = FingerprintUniquePrioContainerMax::new(3);
+    b.extend([4, 3, 0].map(Doc::new));
+    b.inc_push(2);
+
+    a.merge(b);
+    assert_eq!(a.total_pushed(), 8);
+    let out = a.into_sorted_vec().into_iter().map(|d| d.id);
+    assert_eq!(out.collect::<Vec<_>>(), vec![4, 3, 2]);
+}
+
+/// Item which only compares and hashes its key
+#[derive(Debug)]
+struct Tagged(u32, &'static str);
+
+impl PartialEq for Tagged {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for Tagged {}
+
+impl PartialOrd for Tagged {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Tagged {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl Hash for Tagged {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+#[test]
+fn test_fingerprint_duplicate_policy() {
+    let tags = |heap: Vec<Tagged>| heap.into_iter().map(|t| t.1).collect::<Vec<_>>();
+
+    let mut heap = FingerprintUniquePrioContainer::new(2);
+    heap.set_duplicate_policy(DuplicatePolicy::KeepLast);
+    heap.extend([Tagged(1, "a"), Tagged(2, "b"), Tagged(1, "c")]);
+    assert_eq!(tags(heap.into_sorted_vec()), vec!["c", "b"]);
+
+    let mut heap = FingerprintUniquePrioContainerMax::new(2);
+    heap.set_duplicate_policy(DuplicatePolicy::combine(|a: Tagged, b: Tagged| {
+        Tagged(a.0, if a.1 < b.1 { a.1 } else { b.1 })
+    }));
+    heap.extend([Tagged(1, "b"), Tagged(2, "c"), Tagged(1, "a")]);
+    assert_eq!(tags(heap.into_sorted_vec()), vec!["c", "a"]);
+}
+
+#[test]
+fn test_duplicate_policy_borrowed() {
+    // The Max containers accept a duplicate policy for items that aren't `'static`
+    let text = String::from("b a c a");
+    let words = text.split(' ').collect::<Vec<_>>();
+
+    let mut heap = FingerprintUniquePrioContainerMax::new(2);
+    heap.set_duplicate_policy(DuplicatePolicy::KeepLast);
+    heap.extend(words.iter().copied());
+    assert_eq!(heap.into_sorted_vec(), vec!["c", "b"]);
+
+    let mut heap = UniquePrioContainerMax::new(2);
+    heap.set_duplicate_policy(DuplicatePolicy::KeepLast);
+    heap.extend(words.iter().copied());
+    assert_eq!(heap.into_sorted_vec(), vec!["c", "b"]);
+
+    let mut heap = StableUniquePrioContainerMax::new(2);
+    heap.set_duplicate_policy(DuplicatePolicy::combine(|a, _| a));
+    heap.extend(words);
+    assert_eq!(heap.into_sorted_vec(), vec!["c", "b"]);
+}
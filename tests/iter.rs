@@ -0,0 +1,95 @@
+use priority_container::{
+    FingerprintUniquePrioContainerMax, PrioContainer, PrioContainerMax, StablePrioContainer,
+    StablePrioContainerMax, StableUniquePrioContainer, StableUniquePrioContainerMax,
+    UniquePrioContainer,
+};
+
+const INPUT: [(u32, char); 6] = [(3, 'a'), (1, 'b'), (3, 'c'), (2, 'd'), (5, 'e'), (1, 'f')];
+
+#[test]
+fn test_best_worst_first() {
+    let mut heap = PrioContainer::new(4);
+    heap.extend([4, 1, 3, 5, 2]);
+    let best = heap.into_iter().best_first().collect::<Vec<_>>();
+    assert_eq!(best, vec![1, 2, 3, 4]);
+
+    let mut heap = UniquePrioContainer::new(4);
+    heap.extend([4, 1, 3, 3, 5, 2]);
+    let worst = heap.into_iter().worst_first().collect::<Vec<_>>();
+    assert_eq!(worst, vec![4, 3, 2, 1]);
+
+    let mut heap = StablePrioContainerMax::new(4);
+    heap.extend(INPUT.map(|(score, _)| score));
+    let best = heap.into_iter().best_first().collect::<Vec<_>>();
+    assert_eq!(best, vec![5, 3, 3, 2]);
+}
+
+#[test]
+fn test_double_ended() {
+    let mut heap = PrioContainerMax::new(5);
+    heap.extend([4, 1, 3, 5, 2, 6]);
+    let mut iter = heap.into_iter();
+    assert_eq!(iter.len(), 5);
+    assert_eq!(iter.next(), Some(2));
+    assert_eq!(iter.next_back(), Some(6));
+    assert_eq!(iter.next(), Some(3));
+    assert_eq!(iter.len(), 2);
+    assert_eq!(iter.next_back(), Some(5));
+    assert_eq!(iter.next(), Some(4));
+    assert_eq!(iter.next_back(), None);
+    assert_eq!(iter.next(), None);
+
+    // Iterating from the back first sorts the remaining items
+    let mut heap = StablePrioContainer::new(6);
+    heap.extend(INPUT.map(|(score, id)| (score, id)));
+    let mut iter = heap.into_iter();
+    assert_eq!(iter.next_back(), Some((1, 'b')));
+    assert_eq!(iter.next(), Some((5, 'e')));
+    assert_eq!(iter.next_back(), Some((1, 'f')));
+    let rest = iter.collect::<Vec<_>>();
+    assert_eq!(rest, vec![(3, 'c'), (3, 'a'), (2, 'd')]);
+}
+
+#[test]
+fn test_stable_iter_rev() {
+    let mut heap = StableUniquePrioContainerMax::new(4);
+    heap.extend([3, 1, 3, 2, 5, 1]);
+    let iter = heap.into_iter();
+    assert_eq!(iter.len(), 4);
+    assert_eq!(iter.rev().collect::<Vec<_>>(), vec![5, 3, 2, 1]);
+}
+
+#[test]
+fn test_unsorted() {
+    let mut heap = StablePrioContainerMax::new(3);
+    heap.extend(INPUT.map(|(score, _)| score));
+    let mut items = heap
+        .into_heap_items()
+        .into_iter()
+        .map(|i| (i.counter(), *i.inner()))
+        .collect::<Vec<_>>();
+    items.sort_unstable();
+    assert_eq!(items, vec![(1, 3), (3, 3), (5, 5)]);
+
+    let mut heap = StableUniquePrioContainer::new(3);
+    heap.extend(INPUT.map(|(score, _)| score));
+    let mut items = heap.into_heap_items();
+    items.sort_unstable();
+    let counters = items.iter().map(|i| i.counter()).collect::<Vec<_>>();
+    assert_eq!(counters, vec![2, 4, 1]);
+
+    // The remaining items of a partially consumed iterator
+    let mut heap = PrioContainer::new(4);
+    heap.extend([4, 1, 3, 5, 2]);
+    let mut iter = heap.into_iter();
+    assert_eq!(iter.next_back(), Some(1));
+    let mut rest = iter.into_vec();
+    rest.sort_unstable();
+    assert_eq!(rest, vec![2, 3, 4]);
+
+    let mut heap = FingerprintUniquePrioContainerMax::new(2);
+    heap.extend([4, 1, 4, 5, 2]);
+    let mut items = heap.into_unsorted_iter().collect::<Vec<_>>();
+    items.sort_unstable();
+    assert_eq!(items, vec![4, 5]);
+}
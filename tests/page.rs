@@ -0,0 +1,34 @@
+use priority_container::*;
+
+#[test]
+fn test_page() {
+    let mut page = Paginated::<PrioContainerMax<u32>>::new(3, 2);
+    page.extend(0..100);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.total_pushed(), 100);
+    assert_eq!(page.iter().copied().collect::<Vec<_>>(), vec![96, 95]);
+    assert_eq!(page.into_iter().collect::<Vec<_>>(), vec![96, 95]);
+}
+
+#[test]
+fn test_page_partial() {
+    let mut page = Paginated::<PrioContainer<u32>>::new(3, 4);
+    page.extend([5, 1, 4]);
+    assert!(page.is_empty());
+
+    page.extend([2, 3]);
+    assert_eq!(page.into_iter().collect::<Vec<_>>(), vec![4, 5]);
+}
+
+#[test]
+fn test_page_stable() {
+    let mut page = Paginated::<StablePrioContainerMax<(u32, &str)>>::new(1, 2);
+    page.extend([(1, "a"), (3, "b"), (2, "c"), (3, "d")]);
+    let out = page.into_iter().map(|i| i.1).collect::<Vec<_>>();
+    assert_eq!(out, vec!["b", "c"]);
+}
+
+#[test]
+fn test_page_zero_limit() {
+    assert!(Paginated::<PrioContainer<u32>>::try_new(10, 0).is_err());
+}
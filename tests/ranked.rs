@@ -0,0 +1,70 @@
+use priority_container::{
+    stable::item::HeapItem, RankMode, StablePrioContainer, StablePrioContainerMax,
+};
+
+fn ranks(mode: RankMode) -> Vec<usize> {
+    let mut heap = StablePrioContainerMax::new(6);
+    heap.extend([5, 3, 5, 1, 3, 3]);
+    heap.into_iter()
+        .ranked(mode)
+        .map(|(rank, ..)| rank)
+        .collect()
+}
+
+#[test]
+fn test_rank_modes() {
+    assert_eq!(ranks(RankMode::Ordinal), vec![1, 2, 3, 4, 5, 6]);
+    assert_eq!(ranks(RankMode::Competition), vec![1, 1, 3, 3, 3, 6]);
+    assert_eq!(ranks(RankMode::Dense), vec![1, 1, 2, 2, 2, 3]);
+}
+
+#[test]
+fn test_ranked_fractional() {
+    let mut heap = StablePrioContainerMax::new(6);
+    heap.extend([5, 3, 5, 1, 3, 3]);
+    let out = heap.into_iter().ranked_fractional().collect::<Vec<_>>();
+    assert_eq!(
+        out,
+        vec![
+            (1.5, 3, 5),
+            (1.5, 1, 5),
+            (4.0, 6, 3),
+            (4.0, 5, 3),
+            (4.0, 2, 3),
+            (6.0, 4, 1)
+        ]
+    );
+}
+
+#[test]
+fn test_ranked_seq() {
+    let mut heap = StablePrioContainerMax::new(6);
+    heap.extend([5, 3, 5, 1, 3, 3]);
+    let out = heap.into_iter().ranked(RankMode::Dense).collect::<Vec<_>>();
+    assert_eq!(
+        out,
+        vec![
+            (1, 3, 5),
+            (1, 1, 5),
+            (2, 6, 3),
+            (2, 5, 3),
+            (2, 2, 3),
+            (3, 4, 1)
+        ]
+    );
+
+    let mut heap = StablePrioContainer::new(3);
+    heap.extend([4, 2, 2, 9, 1]);
+    let mut iter = heap.into_iter();
+    // Dropping the worst item ranks only the remaining ones
+    assert_eq!(iter.next(), Some(2));
+    let out = iter.ranked(RankMode::Competition).collect::<Vec<_>>();
+    assert_eq!(out, vec![(1, 5, 1), (2, 3, 2)]);
+}
+
+#[test]
+fn test_heap_item_counter() {
+    let item = HeapItem::new("a", 7);
+    assert_eq!(item.counter(), 7);
+    assert_eq!(*item.inner(), "a");
+}
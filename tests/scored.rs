@@ -0,0 +1,45 @@
+use priority_container::*;
+
+/// Payload which doesn't implement `Ord`
+#[derive(Debug, PartialEq)]
+struct Payload(Vec<u8>);
+
+#[test]
+fn test_scored() {
+    let mut container = ScoredPrioContainerMax::new(2);
+    container.insert(3, Payload(vec![3]));
+    container.insert(1, Payload(vec![1]));
+    container.insert(5, Payload(vec![5]));
+    assert_eq!(container.threshold(), Some(&3));
+    assert!(!container.would_accept(&2));
+
+    let best = container.iter_sorted().next().unwrap();
+    assert_eq!(best, (&5, &Payload(vec![5])));
+
+    let out = container.into_iter().collect::<Vec<_>>();
+    assert_eq!(out, vec![(3, Payload(vec![3])), (5, Payload(vec![5]))]);
+}
+
+#[test]
+fn test_scored_stable() {
+    let mut container = StableScoredPrioContainer::new(3);
+    container.extend([(1, "a"), (0, "b"), (1, "c"), (1, "d")]);
+
+    let out = container.into_iter().collect::<Vec<_>>();
+    assert_eq!(out, vec![(1, "c"), (1, "d"), (0, "b")]);
+}
+
+#[test]
+fn test_scored_unique() {
+    let mut container = UniqueScoredPrioContainerMax::new(3);
+    container.extend([(1, "a"), (2, "b"), (5, "a"), (1, "c")]);
+    assert_eq!(container.len(), 3);
+
+    let out = container.iter_sorted().collect::<Vec<_>>();
+    assert_eq!(out, vec![(&5, &"a"), (&2, &"b"), (&1, &"c")]);
+
+    let mut container = StableUniqueScoredPrioContainerMax::new(3);
+    container.extend([(1, "a"), (1, "b"), (1, "a")]);
+    container.retain(|_, value| *value != "b");
+    assert_eq!(container.into_iter().collect::<Vec<_>>(), vec![(1, "a")]);
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::non_canonical_partial_ord_impl)]
+
 use std::{collections::HashSet, hash::Hash};
 
 use priority_container::{unique::max::UniquePrioContainerMax, *};
@@ -44,8 +46,7 @@ fn test_max_with_capacity(inp_len: usize, capacity: usize) {
 
     let mut prio_container = PrioContainerMax::new(capacity);
     prio_container.extend(input);
-    let mut out = prio_container.into_iter().map(|i| i.0).collect::<Vec<_>>();
-    out.reverse();
+    let out = prio_container.into_iter().rev().collect::<Vec<_>>();
     assert_eq!(out, expected);
 }
 
@@ -57,8 +58,7 @@ fn test_min_with_capacity(inp_len: usize, capacity: usize) {
 
     let mut prio_container = PrioContainer::new(capacity);
     prio_container.extend(input);
-    let mut out = prio_container.into_iter().collect::<Vec<_>>();
-    out.reverse();
+    let out = prio_container.into_iter().best_first().collect::<Vec<_>>();
     assert_eq!(out, expected);
 }
 
@@ -137,3 +137,248 @@ impl<T> Ord for UniqueItem<T> {
         self.val.cmp(&other.val)
     }
 }
+
+#[test]
+fn test_threshold() {
+    let mut queue = PrioContainer::new(2);
+    queue.insert(5);
+    assert_eq!(queue.threshold(), None);
+    assert!(queue.would_accept(&100));
+    queue.insert(3);
+    assert_eq!(queue.threshold(), Some(&5));
+    assert!(queue.would_accept(&4));
+    assert!(!queue.would_accept(&5));
+
+    let mut queue = PrioContainerMax::new(2);
+    queue.extend([1, 7, 3]);
+    assert_eq!(queue.threshold(), Some(&3));
+    assert!(queue.would_accept(&4));
+    assert!(!queue.would_accept(&3));
+}
+
+#[test]
+fn test_threshold_stable() {
+    let mut queue = StablePrioContainer::new(2);
+    queue.extend([1, 7, 3]);
+    assert_eq!(queue.threshold(), Some(&3));
+    // Newer items win ties
+    assert!(queue.would_accept(&3));
+    assert!(!queue.would_accept(&4));
+
+    let mut queue = StablePrioContainerMax::new(2);
+    queue.extend([1, 7, 3]);
+    assert_eq!(queue.threshold(), Some(&3));
+    assert!(queue.would_accept(&3));
+    assert!(!queue.would_accept(&2));
+}
+
+#[test]
+fn test_insert_with() {
+    let mut queue = PrioContainerMax::new(2);
+    queue.extend([5, 7]);
+
+    let mut built = 0;
+    assert!(!queue.insert_with(&4, || {
+        built += 1;
+        4
+    }));
+    assert_eq!(built, 0);
+
+    // The bound passes but the built item is worse
+    assert!(!queue.insert_with(&9, || {
+        built += 1;
+        3
+    }));
+    assert!(queue.insert_with(&9, || {
+        built += 1;
+        8
+    }));
+    assert_eq!(built, 2);
+    assert_eq!(queue.total_pushed(), 5);
+    assert_eq!(queue.threshold(), Some(&7));
+
+    let mut queue = StablePrioContainer::new(1);
+    queue.insert(3);
+    assert!(!queue.insert_with(&4, || unreachable!()));
+    assert!(queue.insert_with(&3, || 3));
+    assert_eq!(queue.total_pushed(), 3);
+}
+
+#[test]
+fn test_merge() {
+    let input = generate_data(1000);
+
+    let mut expected = PrioContainerMax::new(50);
+    expected.extend(input.iter().copied());
+
+    let mut merged = PrioContainerMax::new(50);
+    for shard in input.chunks(300) {
+        let mut container = PrioContainerMax::new(50);
+        container.extend(shard.iter().copied());
+        merged.merge(container);
+    }
+
+    assert_eq!(merged.total_pushed(), expected.total_pushed());
+    let merged = merged.into_iter().collect::<Vec<_>>();
+    let expected = expected.into_iter().collect::<Vec<_>>();
+    assert_eq!(merged, expected);
+}
+
+#[test]
+fn test_merge_stable() {
+    let input = (0..1000)
+        .map(|i| UniqueItem::new(i, i % 7))
+        .collect::<Vec<_>>();
+
+    let mut expected = StablePrioContainerMax::new(50);
+    expected.extend(input.iter().cloned());
+
+    let mut merged = StablePrioContainerMax::new(50);
+    for shard in input.chunks(300) {
+        let mut container = StablePrioContainerMax::new(50);
+        container.extend(shard.iter().cloned());
+        merged.merge(container);
+    }
+
+    assert_eq!(merged.total_pushed(), expected.total_pushed());
+    let merged = merged.into_iter().map(|i| i.item).collect::<Vec<_>>();
+    let expected = expected.into_iter().map(|i| i.item).collect::<Vec<_>>();
+    assert_eq!(merged, expected);
+}
+
+#[test]
+fn test_iter_sorted() {
+    let mut queue = PrioContainer::new(3);
+    queue.extend([7, 3, 9]);
+    assert_eq!(
+        queue.iter_sorted().copied().collect::<Vec<_>>(),
+        vec![3, 7, 9]
+    );
+    assert_eq!(queue.iter_sorted().len(), 3);
+
+    // The cached order must be invalidated by changes
+    queue.insert(1);
+    assert_eq!(queue.to_sorted_vec(), vec![1, 3, 7]);
+    queue.insert(10);
+    assert_eq!(queue.to_sorted_vec(), vec![1, 3, 7]);
+
+    let mut queue = StablePrioContainerMax::new(3);
+    queue.extend([UniqueItem::new("a", 1), UniqueItem::new("b", 2)]);
+    let out = queue.iter_sorted().map(|i| i.item).collect::<Vec<_>>();
+    assert_eq!(out, vec!["b", "a"]);
+    queue.insert(UniqueItem::new("c", 3));
+    let out = queue.iter_sorted().map(|i| i.item).collect::<Vec<_>>();
+    assert_eq!(out, vec!["c", "b", "a"]);
+}
+
+#[test]
+fn test_try_new() {
+    assert_eq!(
+        PrioContainer::<u32>::try_new(0).err(),
+        Some(Error::ZeroCapacity)
+    );
+    assert!(PrioContainerMax::<u32>::try_new(0).is_err());
+    assert!(StablePrioContainer::<u32>::try_new(0).is_err());
+    assert!(StablePrioContainerMax::<u32>::try_new(0).is_err());
+    assert!(UniquePrioContainer::<u32>::try_new(0).is_err());
+    assert!(UniquePrioContainerMax::<u32>::try_new(0).is_err());
+    assert!(StableUniquePrioContainer::<u32>::try_new(0).is_err());
+    assert!(StableUniquePrioContainerMax::<u32>::try_new(0).is_err());
+
+    let mut queue = UniquePrioContainerMax::try_new(2).unwrap();
+    assert_eq!(queue.try_insert(1), Ok(true));
+    assert_eq!(queue.try_insert(1), Ok(false));
+    assert_eq!(queue.try_insert(2), Ok(true));
+    assert_eq!(queue.try_insert(0), Ok(false));
+}
+
+#[test]
+#[should_panic]
+fn test_unique_zero_capacity() {
+    UniquePrioContainer::<u32>::new(0);
+}
+
+#[test]
+fn test_set_capacity() {
+    let mut queue = PrioContainerMax::new(4);
+    queue.extend([1, 5, 3, 4]);
+    assert_eq!(queue.set_capacity(2), Ok(false));
+    assert_eq!(queue.len(), 2);
+    assert_eq!(queue.to_sorted_vec(), vec![5, 4]);
+
+    // Items 1 and 3 have already been dropped
+    assert_eq!(queue.set_capacity(3), Ok(true));
+    queue.insert(2);
+    assert_eq!(queue.to_sorted_vec(), vec![5, 4, 2]);
+    assert_eq!(queue.set_capacity(0), Err(Error::ZeroCapacity));
+
+    let mut queue = PrioContainer::new(2);
+    queue.insert(1);
+    assert_eq!(queue.set_capacity(3), Ok(false));
+
+    let mut queue = StablePrioContainer::new(5);
+    queue.extend((0..5).map(|i| UniqueItem::new(i, 0)));
+    queue.set_capacity(3).unwrap();
+    queue.insert(UniqueItem::new(5, 0));
+    let out = queue.into_iter().map(|i| i.item).collect::<Vec<_>>();
+    assert_eq!(out, vec![3, 4, 5]);
+}
+
+#[test]
+fn test_set_capacity_nothing_dropped() {
+    // Removing, retaining or counting pushes doesn't drop items for their rank
+    let mut queue = PrioContainer::new(3);
+    queue.extend([1, 2, 3]);
+    assert!(queue.remove(&2));
+    queue.retain(|i| *i != 3);
+    queue.inc_push(5);
+    assert_eq!(queue.set_capacity(5), Ok(false));
+
+    // Items evicted by shrinking count as dropped
+    queue.extend([4, 5]);
+    queue.set_capacity(1).unwrap();
+    assert_eq!(queue.set_capacity(2), Ok(true));
+
+    // So do items rejected by `insert_with`
+    let mut queue = StablePrioContainer::new(2);
+    queue.extend([1, 2]);
+    assert!(!queue.insert_with(&3, || 3));
+    assert_eq!(queue.set_capacity(3), Ok(true));
+
+    // Merged containers keep the dropped state of both
+    let mut queue = PrioContainerMax::new(2);
+    let mut other = PrioContainerMax::new(1);
+    other.extend([1, 2]);
+    queue.merge(other);
+    assert_eq!(queue.len(), 1);
+    assert_eq!(queue.set_capacity(3), Ok(true));
+}
+
+#[test]
+fn test_retain_remove() {
+    let mut queue = PrioContainerMax::new(3);
+    queue.extend([1, 5, 3, 4]);
+    assert!(queue.remove(&4));
+    assert!(!queue.remove(&1));
+    assert_eq!(queue.to_sorted_vec(), vec![5, 3]);
+    queue.retain(|i| *i > 3);
+    assert_eq!(queue.to_sorted_vec(), vec![5]);
+
+    let mut queue = StablePrioContainer::new(4);
+    queue.extend((0..4).map(|i| UniqueItem::new(i, i as u32 % 2)));
+    queue.retain(|i| i.item != 2);
+    queue.insert(UniqueItem::new(4, 0));
+    let out = queue.into_iter().map(|i| i.item).collect::<Vec<_>>();
+    assert_eq!(out, vec![1, 3, 0, 4]);
+}
+
+#[test]
+fn test_glob_import_keeps_std_result() {
+    let parsed: Result<u32, std::num::ParseIntError> = "3".parse();
+    let mut queue = PrioContainer::try_new(1).unwrap();
+    queue.insert(parsed.unwrap());
+    assert!(matches!(
+        PrioContainer::<u32>::try_new(0),
+        Err(Error::ZeroCapacity)
+    ));
+}
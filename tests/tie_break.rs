@@ -0,0 +1,234 @@
+use priority_container::{
+    DuplicatePolicy, HeapKey, StablePrioContainer, StablePrioContainerMax,
+    StableUniquePrioContainer, StableUniquePrioContainerMax, TieBreak, TopKContainer,
+};
+use std::{
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+};
+
+/// Item ranked by its score only, so items with different ids tie
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct Hit {
+    id: u32,
+    score: u32,
+}
+
+impl Ord for Hit {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score.cmp(&other.score)
+    }
+}
+
+impl PartialOrd for Hit {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Five hits with the same score, where the id is the push order
+fn hits() -> Vec<Hit> {
+    (1..=5).map(|id| Hit { id, score: 1 }).collect()
+}
+
+fn ids<'a>(iter: impl Iterator<Item = &'a Hit>) -> Vec<u32> {
+    iter.map(|h| h.id).collect()
+}
+
+#[test]
+fn test_lifo_fifo() {
+    let mut heap = StablePrioContainer::new(3);
+    heap.extend(hits());
+    assert_eq!(ids(heap.iter_sorted()), vec![5, 4, 3]);
+
+    let mut heap = StablePrioContainer::with_tie_break(3, TieBreak::Fifo);
+    heap.extend(hits());
+    assert_eq!(ids(heap.iter_sorted()), vec![1, 2, 3]);
+    assert!(!heap.would_accept(&Hit { id: 6, score: 1 }));
+    assert!(heap.would_accept(&Hit { id: 6, score: 0 }));
+
+    let mut heap = StablePrioContainerMax::with_tie_break(3, TieBreak::Fifo);
+    heap.extend(hits());
+    heap.insert(Hit { id: 6, score: 0 });
+    assert_eq!(ids(heap.iter_sorted()), vec![1, 2, 3]);
+
+    let mut heap = StableUniquePrioContainerMax::with_tie_break(3, TieBreak::Fifo);
+    heap.extend(hits());
+    assert_eq!(ids(heap.iter_sorted()), vec![1, 2, 3]);
+}
+
+#[test]
+fn test_random() {
+    let run = |seed| {
+        let mut heap = StablePrioContainer::with_tie_break(3, TieBreak::Random(seed));
+        heap.extend(hits());
+        ids(heap.iter_sorted())
+    };
+    assert_eq!(run(7), run(7));
+    assert!((0..16).any(|seed| run(seed) != run(7)));
+
+    // Merging shards gives the same result as pushing everything into one container
+    let mut left = StableUniquePrioContainer::with_tie_break(3, TieBreak::Random(7));
+    let mut right = StableUniquePrioContainer::with_tie_break(3, TieBreak::Random(7));
+    left.extend(hits().into_iter().take(2));
+    right.extend(hits().into_iter().skip(2));
+    left.merge(right);
+    assert_eq!(ids(left.iter_sorted()), run(7));
+}
+
+#[test]
+fn test_sequence() {
+    // Descending ids so the last pushed item has the smallest sequence number
+    let seq = TieBreak::sequence(|h: &Hit| u64::from(10 - h.id));
+    let mut heap = StableUniquePrioContainer::with_tie_break(3, seq);
+    heap.extend(hits());
+    assert_eq!(ids(heap.iter_sorted()), vec![5, 4, 3]);
+    assert!(heap.would_accept(&Hit { id: 6, score: 1 }));
+    assert!(!heap.would_accept(&Hit { id: 0, score: 1 }));
+
+    let mut heap =
+        StablePrioContainerMax::with_tie_break(3, TieBreak::sequence(|h: &Hit| h.id.into()));
+    heap.extend(hits().into_iter().rev());
+    assert_eq!(ids(heap.iter_sorted()), vec![1, 2, 3]);
+}
+
+#[test]
+fn test_set_tie_break() {
+    let mut heap = StablePrioContainerMax::new(5);
+    heap.extend(hits());
+    assert_eq!(ids(heap.iter_sorted()), vec![5, 4, 3, 2, 1]);
+
+    heap.set_tie_break(TieBreak::Fifo);
+    assert_eq!(ids(heap.iter_sorted()), vec![1, 2, 3, 4, 5]);
+    assert_eq!(
+        heap.into_iter().map(|h| h.id).collect::<Vec<_>>(),
+        vec![5, 4, 3, 2, 1]
+    );
+
+    let mut heap = StableUniquePrioContainerMax::new(5);
+    heap.extend(hits());
+    heap.set_tie_break(TieBreak::sequence(|h: &Hit| u64::from(h.id % 3)));
+    assert_eq!(ids(heap.iter_sorted()), vec![3, 4, 1, 5, 2]);
+}
+
+#[test]
+fn test_would_accept_matches_insert() {
+    let tie_breaks = || {
+        vec![
+            TieBreak::Lifo,
+            TieBreak::Fifo,
+            TieBreak::Random(3),
+            TieBreak::sequence(|h: &Hit| h.id.into()),
+            TieBreak::sequence(|h: &Hit| u64::from(10 - h.id)),
+        ]
+    };
+    type New = fn(TieBreak<Hit>) -> Box<dyn TopKContainer<Hit>>;
+    let containers: [New; 4] = [
+        |t| Box::new(StablePrioContainer::with_tie_break(3, t)),
+        |t| Box::new(StablePrioContainerMax::with_tie_break(3, t)),
+        |t| Box::new(StableUniquePrioContainer::with_tie_break(3, t)),
+        |t| Box::new(StableUniquePrioContainerMax::with_tie_break(3, t)),
+    ];
+    let candidates = [0, 6].map(|id| Hit { id, score: 1 });
+
+    for new in containers {
+        for i in 0..tie_breaks().len() {
+            for candidate in candidates {
+                let mut heap = new(tie_breaks().swap_remove(i));
+                for hit in hits() {
+                    heap.insert(hit);
+                }
+
+                let accepted = heap.would_accept(&candidate);
+                assert_eq!(heap.insert(candidate), accepted, "{i} {candidate:?}");
+            }
+        }
+    }
+}
+
+/// Generic code over the stable container only needs to carry the `HeapKey` bound along
+fn best<T: Ord + Copy + HeapKey<K>, K>(heap: &StablePrioContainer<T, K>) -> Option<T> {
+    heap.iter_sorted().next().copied()
+}
+
+#[test]
+fn test_generic_over_key() {
+    let mut heap = StablePrioContainer::with_tie_break(2, TieBreak::Fifo);
+    heap.extend(hits());
+    assert_eq!(best(&heap).map(|h| h.id), Some(1));
+}
+
+#[test]
+fn test_borrowed_items() {
+    // The Max containers don't require `'static` items for any tie break
+    let text = String::from("b a c a");
+    let words = text.split(' ').collect::<Vec<_>>();
+    let seq = TieBreak::sequence(|w: &&str| w.len() as u64);
+
+    let mut heap = StableUniquePrioContainerMax::with_tie_break(2, TieBreak::Fifo);
+    heap.extend(words.iter().copied());
+    heap.set_tie_break(seq);
+    assert_eq!(heap.into_sorted_vec(), vec!["c", "b"]);
+
+    let mut heap = StablePrioContainerMax::with_tie_break(2, TieBreak::Lifo);
+    heap.extend(words);
+    assert_eq!(heap.into_sorted_vec(), vec!["c", "b"]);
+}
+
+/// Item deduped by its id and ranked by its score, with a sequence number on the side
+#[derive(Clone, Copy, Debug)]
+struct Event {
+    id: u32,
+    score: u32,
+    seq: u64,
+}
+
+impl PartialEq for Event {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Event {}
+
+impl Hash for Event {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl Ord for Event {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score.cmp(&other.score)
+    }
+}
+
+impl PartialOrd for Event {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[test]
+fn test_replaced_item_sequence() {
+    let event = |id, seq| Event { id, score: 1, seq };
+    let seq = TieBreak::sequence(|e: &Event| e.seq);
+    let mut heap = StableUniquePrioContainer::with_tie_break(2, seq);
+    heap.set_duplicate_policy(DuplicatePolicy::KeepLast);
+    heap.extend([event(1, 5), event(2, 3)]);
+
+    // The replacement ranks by its own sequence number
+    heap.insert(event(1, 1));
+    let order = heap
+        .iter_sorted()
+        .map(|e| (e.id, e.seq))
+        .collect::<Vec<_>>();
+    assert_eq!(order, vec![(1, 1), (2, 3)]);
+
+    heap.insert(event(3, 2));
+    let order = heap
+        .iter_sorted()
+        .map(|e| (e.id, e.seq))
+        .collect::<Vec<_>>();
+    assert_eq!(order, vec![(1, 1), (3, 2)]);
+}
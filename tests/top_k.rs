@@ -0,0 +1,54 @@
+use priority_container::*;
+
+fn containers(capacity: usize) -> Vec<Box<dyn TopKContainer<u32>>> {
+    vec![
+        Box::new(PrioContainer::new(capacity)),
+        Box::new(StablePrioContainer::new(capacity)),
+        Box::new(UniquePrioContainer::new(capacity)),
+        Box::new(StableUniquePrioContainer::new(capacity)),
+    ]
+}
+
+fn containers_max(capacity: usize) -> Vec<Box<dyn TopKContainer<u32>>> {
+    vec![
+        Box::new(PrioContainerMax::new(capacity)),
+        Box::new(StablePrioContainerMax::new(capacity)),
+        Box::new(UniquePrioContainerMax::new(capacity)),
+        Box::new(StableUniquePrioContainerMax::new(capacity)),
+        Box::new(PrioContainer::by_key(capacity, |i: &u32| {
+            std::cmp::Reverse(*i)
+        })),
+    ]
+}
+
+fn fill(container: &mut dyn TopKContainer<u32>) {
+    for i in [7, 3, 9, 1, 5] {
+        container.insert(i);
+    }
+}
+
+#[test]
+fn test_dyn_min() {
+    for mut container in containers(3) {
+        fill(container.as_mut());
+        assert_eq!(container.len(), 3);
+        assert_eq!(container.capacity(), 3);
+        assert_eq!(container.total_pushed(), 5);
+        assert_eq!(container.threshold(), Some(&5));
+        assert!(container.would_accept(&4));
+        assert_eq!(container.to_sorted_vec(), vec![1, 3, 5]);
+        assert_eq!(container.drain_sorted(), vec![1, 3, 5]);
+        assert!(container.is_empty());
+    }
+}
+
+#[test]
+fn test_dyn_max() {
+    for mut container in containers_max(3) {
+        fill(container.as_mut());
+        assert_eq!(container.threshold(), Some(&5));
+        assert!(container.would_accept(&6));
+        assert_eq!(container.iter_sorted().next_back(), Some(&5));
+        assert_eq!(container.drain_sorted(), vec![9, 7, 5]);
+    }
+}
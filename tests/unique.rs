@@ -1,6 +1,12 @@
+#![allow(clippy::non_canonical_partial_ord_impl, clippy::useless_conversion)]
+
 use std::hash::Hash;
 
-use priority_container::{unique::max::UniquePrioContainerMax, StableUniquePrioContainer};
+use priority_container::{
+    unique::max::UniquePrioContainerMax, DedupPolicy, DuplicatePolicy,
+    FingerprintUniquePrioContainer, StableUniquePrioContainer, StableUniquePrioContainerMax,
+    UniquePrioContainer,
+};
 
 fn make_invariant_test(len: usize, max: usize) -> UniquePrioContainerMax<UniqueItem<usize>> {
     let mut heap = UniquePrioContainerMax::new(max);
@@ -106,3 +112,294 @@ fn test_stability_simple_unique() {
         assert_eq!(out, vec!["9", "8", "7", "a", "b", "c", "d", "e"]);
     }
 }
+
+#[test]
+fn test_threshold_unique() {
+    let mut heap = UniquePrioContainer::new(2);
+    heap.extend([4, 4, 9, 2]);
+    assert_eq!(heap.threshold(), Some(&4));
+    assert!(heap.would_accept(&3));
+    assert!(!heap.would_accept(&4));
+
+    let mut heap = UniquePrioContainerMax::new(2);
+    heap.extend([4, 4, 9, 2]);
+    assert_eq!(heap.threshold(), Some(&4));
+    assert!(heap.would_accept(&5));
+    assert!(!heap.would_accept(&4));
+}
+
+#[test]
+fn test_merge_unique() {
+    let mut merged = UniquePrioContainer::new(3);
+    merged.extend([5, 1, 4]);
+
+    let mut other = UniquePrioContainer::new(3);
+    other.extend([1, 2, 4]);
+    merged.merge(other);
+
+    assert_eq!(merged.len(), 3);
+    assert_eq!(merged.into_iter().collect::<Vec<_>>(), vec![4, 2, 1]);
+
+    let mut merged = StableUniquePrioContainer::new(10);
+    merged.extend([UniqueItem::new("a", 1), UniqueItem::new("b", 2)]);
+
+    let mut other = StableUniquePrioContainer::new(10);
+    other.extend([UniqueItem::new("c", 1), UniqueItem::new("a", 0)]);
+    merged.merge(other);
+
+    let out = merged.into_iter().map(|i| i.item).collect::<Vec<_>>();
+    assert_eq!(out, vec!["b", "c", "a"]);
+}
+
+#[test]
+fn test_iter_sorted_unique() {
+    let mut heap = StableUniquePrioContainer::new(10);
+    heap.extend([UniqueItem::new("a", 3), UniqueItem::new("b", 2)]);
+    let out = heap.iter_sorted().map(|i| i.item).collect::<Vec<_>>();
+    assert_eq!(out, vec!["b", "a"]);
+
+    // Replacing a duplicate has to invalidate the cached order
+    heap.insert(UniqueItem::new("a", 1));
+    let out = heap.iter_sorted().map(|i| i.item).collect::<Vec<_>>();
+    assert_eq!(out, vec!["a", "b"]);
+}
+
+#[test]
+fn test_set_capacity_unique() {
+    let mut heap = UniquePrioContainerMax::new(3);
+    heap.extend([3, 2, 1]);
+    assert_eq!(heap.set_capacity(1), Ok(false));
+    assert_eq!(heap.to_sorted_vec(), vec![3]);
+
+    // Evicted items are still known as duplicates
+    heap.set_capacity(3).unwrap();
+    heap.insert(2);
+    assert_eq!(heap.to_sorted_vec(), vec![3]);
+}
+
+#[test]
+fn test_set_capacity_duplicates() {
+    // Rejected duplicates aren't dropped for their rank
+    let mut heap = UniquePrioContainer::new(3);
+    heap.extend([1, 1]);
+    assert_eq!(heap.set_capacity(5), Ok(false));
+
+    let mut heap = StableUniquePrioContainer::new(2);
+    heap.extend([1, 1, 2, 2]);
+    assert!(heap.remove(&1));
+    assert_eq!(heap.set_capacity(3), Ok(false));
+    heap.extend([3, 4, 5]);
+    assert_eq!(heap.set_capacity(4), Ok(true));
+
+    let mut heap = FingerprintUniquePrioContainer::<u32>::new(2);
+    heap.extend([1, 1]);
+    assert_eq!(heap.set_capacity(3), Ok(false));
+}
+
+#[test]
+fn test_retain_remove_unique() {
+    let mut heap = UniquePrioContainerMax::new(3);
+    heap.extend([3, 2, 1]);
+    assert!(heap.remove(&2));
+    assert_eq!(heap.to_sorted_vec(), vec![3, 1]);
+
+    // Removed items can be inserted again
+    heap.insert(2);
+    assert_eq!(heap.to_sorted_vec(), vec![3, 2, 1]);
+
+    let mut heap = StableUniquePrioContainer::new(3);
+    heap.extend([UniqueItem::new("a", 1), UniqueItem::new("b", 2)]);
+    heap.retain(|i| i.item != "a");
+    assert_eq!(heap.len(), 1);
+    heap.insert(UniqueItem::new("a", 0));
+    let out = heap.into_iter().map(|i| i.item).collect::<Vec<_>>();
+    assert_eq!(out, vec!["b", "a"]);
+}
+
+#[test]
+fn test_replace_random_unique() {
+    use rand::{seq::SliceRandom, thread_rng, Rng};
+    use std::collections::HashSet;
+
+    let mut rng = thread_rng();
+    for _ in 0..100 {
+        let mut vals = (0..1000).collect::<Vec<u32>>();
+        vals.shuffle(&mut rng);
+
+        let mut heap = UniquePrioContainer::new(10);
+        let mut stored: Vec<UniqueItem<u32>> = Vec::new();
+        let mut seen = HashSet::new();
+
+        for val in vals {
+            let item = rng.gen_range(0..50);
+
+            if rng.gen_bool(0.05) {
+                let removed = stored.iter().any(|i| i.item == item);
+                stored.retain(|i| i.item != item);
+                seen.remove(&item);
+                assert_eq!(heap.remove(&UniqueItem::new(item, 0)), removed);
+                continue;
+            }
+
+            heap.insert(UniqueItem::new(item, val));
+            if !seen.insert(item) {
+                if let Some(i) = stored.iter_mut().find(|i| i.item == item) {
+                    i.val = i.val.min(val);
+                }
+            } else if stored.len() < 10 {
+                stored.push(UniqueItem::new(item, val));
+            } else {
+                let worst = stored.iter_mut().max().unwrap();
+                if val < worst.val {
+                    *worst = UniqueItem::new(item, val);
+                }
+            }
+
+            assert!(
+                heap.contains(&UniqueItem::new(item, 0)) == stored.iter().any(|i| i.item == item)
+            );
+        }
+
+        stored.sort();
+        let expected = stored.iter().map(|i| (i.item, i.val)).collect::<Vec<_>>();
+        let out = heap
+            .iter_sorted()
+            .map(|i| (i.item, i.val))
+            .collect::<Vec<_>>();
+        assert_eq!(out, expected);
+    }
+}
+
+#[test]
+fn test_dedup_policy() {
+    let items = || {
+        [
+            UniqueItem::new("a", 3),
+            UniqueItem::new("b", 1),
+            UniqueItem::new("c", 2),
+            UniqueItem::new("a", 0),
+        ]
+    };
+
+    // "a" got evicted by "c" and stays remembered, so its better score gets rejected
+    let mut heap = UniquePrioContainer::new(2);
+    assert_eq!(heap.policy(), DedupPolicy::Seen);
+    heap.extend(items());
+    let out = heap.into_iter().map(|i| i.item).collect::<Vec<_>>();
+    assert_eq!(out, vec!["c", "b"]);
+
+    // Evicted items are forgotten and can come back
+    let mut heap = UniquePrioContainer::with_policy(2, DedupPolicy::Retained);
+    heap.extend(items());
+    let out = heap.into_iter().map(|i| i.item).collect::<Vec<_>>();
+    assert_eq!(out, vec!["b", "a"]);
+
+    // Duplicates among the retained items are still detected
+    let mut heap = StableUniquePrioContainerMax::with_policy(2, DedupPolicy::Retained);
+    heap.extend([1, 2, 2, 3, 1, 1]);
+    assert_eq!(heap.to_sorted_vec(), vec![3, 2]);
+    assert_eq!(heap.total_pushed(), 6);
+}
+
+#[test]
+fn test_custom_hasher() {
+    use std::hash::{BuildHasherDefault, Hasher};
+
+    /// Identity hasher for integer IDs
+    #[derive(Default)]
+    struct IdHasher(u64);
+
+    impl Hasher for IdHasher {
+        fn finish(&self) -> u64 {
+            self.0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            for b in bytes {
+                self.0 = self.0.rotate_left(8) ^ u64::from(*b);
+            }
+        }
+
+        fn write_u64(&mut self, i: u64) {
+            self.0 = i;
+        }
+    }
+
+    let hasher = BuildHasherDefault::<IdHasher>::default();
+    let mut heap = UniquePrioContainerMax::with_hasher(3, hasher);
+    heap.extend([1u64, 5, 3, 5, 1, 4]);
+    assert_eq!(heap.to_sorted_vec(), vec![5, 4, 3]);
+
+    let hasher = BuildHasherDefault::<IdHasher>::default();
+    let mut heap = StableUniquePrioContainer::with_capacity_and_hasher(2, hasher);
+    heap.extend([2u64, 1, 2, 0]);
+    assert_eq!(heap.into_iter().collect::<Vec<_>>(), vec![1, 0]);
+
+    // Strings go through `write` instead of `write_u64`
+    let hasher = BuildHasherDefault::<IdHasher>::default();
+    let mut heap = UniquePrioContainer::with_policy_and_hasher(2, DedupPolicy::Retained, hasher);
+    heap.extend(["b", "c", "d", "a"]);
+    assert_eq!(heap.to_sorted_vec(), vec!["a", "b"]);
+    // "c" got evicted and forgotten, so it fits again once "b" is gone
+    assert!(heap.remove(&"b"));
+    assert!(heap.insert("c"));
+    assert_eq!(heap.to_sorted_vec(), vec!["a", "c"]);
+}
+
+#[test]
+fn test_duplicate_policy() {
+    let items = || {
+        [
+            UniqueItem::new("a", 1),
+            UniqueItem::new("b", 2),
+            UniqueItem::new("a", 3),
+            UniqueItem::new("b", 1),
+        ]
+    };
+    let collect = |heap: UniquePrioContainerMax<UniqueItem<&'static str>>| {
+        heap.iter_sorted()
+            .map(|i| (i.item, i.val))
+            .collect::<Vec<_>>()
+    };
+
+    let mut heap = UniquePrioContainerMax::new(3);
+    heap.extend(items());
+    assert_eq!(collect(heap), vec![("a", 3), ("b", 2)]);
+
+    let mut heap = UniquePrioContainerMax::new(3);
+    heap.set_duplicate_policy(DuplicatePolicy::KeepFirst);
+    heap.extend(items());
+    assert_eq!(collect(heap), vec![("b", 2), ("a", 1)]);
+
+    let mut heap = UniquePrioContainerMax::new(3);
+    heap.set_duplicate_policy(DuplicatePolicy::KeepLast);
+    heap.extend(items());
+    assert_eq!(collect(heap), vec![("a", 3), ("b", 1)]);
+
+    // Sum up the scores of equal items
+    let mut heap = UniquePrioContainerMax::new(3);
+    heap.set_duplicate_policy(DuplicatePolicy::combine(|a: UniqueItem<_>, b| {
+        UniqueItem::new(a.item, a.val + b.val)
+    }));
+    heap.extend(items());
+    heap.insert(UniqueItem::new("b", 5));
+    assert_eq!(collect(heap), vec![("b", 8), ("a", 4)]);
+}
+
+#[test]
+fn test_duplicate_policy_stable() {
+    let mut heap = StableUniquePrioContainer::new(3);
+    heap.set_duplicate_policy(DuplicatePolicy::combine(|a: UniqueItem<_>, b| {
+        UniqueItem::new(a.item, a.val.max(b.val))
+    }));
+    heap.extend([
+        UniqueItem::new("a", 2),
+        UniqueItem::new("b", 1),
+        UniqueItem::new("c", 2),
+        UniqueItem::new("b", 2),
+    ]);
+
+    // "b" keeps its position among the equally ranked items
+    let out = heap.into_iter().map(|i| i.item).collect::<Vec<_>>();
+    assert_eq!(out, vec!["a", "b", "c"]);
+}